@@ -0,0 +1,231 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use aes::Aes128;
+use pbkdf2::pbkdf2_hmac;
+use sha1::Sha1;
+use sqlx::SqliteConnection;
+use tokio::process::Command;
+
+use crate::error::LsarResult;
+
+use super::{domain_condition, BrowserCookie};
+
+type Aes128CbcDec = cbc::Decryptor<Aes128>;
+
+const SALT: &[u8] = b"saltysalt";
+const IV: [u8; 16] = [b' '; 16];
+/// 没有使用系统密钥环时的固定密码
+const V10_PASSWORD: &[u8] = b"peanuts";
+/// 从此版本起，解密后的值前有 32 字节的域名哈希
+const HOST_HASH_VERSION: i64 = 24;
+const HOST_HASH_LEN: usize = 32;
+/// 等待 secret-tool 的时间，密钥环被锁定时它可能一直等待解锁
+const KEYRING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 基于 Chromium 的浏览器：名称、密钥环中的应用名、用户数据目录。
+/// 只支持 Linux 的加密方式，其他系统上的 cookie 由钥匙串或 DPAPI 加密，无法解密
+pub(super) fn user_data_dirs() -> Vec<(&'static str, &'static str, PathBuf)> {
+    if !cfg!(target_os = "linux") {
+        debug!("Chromium cookies are only supported on Linux");
+        return Vec::new();
+    }
+    let Some(root) = dirs::config_dir() else {
+        return Vec::new();
+    };
+
+    [
+        ("Chrome", "chrome", "google-chrome"),
+        ("Chromium", "chromium", "chromium"),
+        ("Edge", "microsoft-edge", "microsoft-edge"),
+        ("Brave", "brave", "BraveSoftware/Brave-Browser"),
+    ]
+    .into_iter()
+    .map(|(browser, app, dir)| (browser, app, root.join(dir)))
+    .collect()
+}
+
+/// v11 加密使用的密码的来源
+#[derive(Debug)]
+pub(super) enum Keyring {
+    /// 按应用名从系统密钥环中读取
+    App(&'static str),
+    /// 已知的密码，测试时使用
+    #[cfg(test)]
+    Password(&'static str),
+}
+
+impl Keyring {
+    pub(super) async fn password(&self) -> Option<String> {
+        match self {
+            Keyring::App(app) => keyring_password(app).await,
+            #[cfg(test)]
+            Keyring::Password(password) => Some((*password).to_owned()),
+        }
+    }
+}
+
+/// 从系统密钥环中读取 v11 加密使用的密码，只支持 Linux
+async fn keyring_password(app: &str) -> Option<String> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+
+    let output = Command::new("secret-tool")
+        .args(["lookup", "application", app])
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = match tokio::time::timeout(KEYRING_TIMEOUT, output).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => {
+            debug!("Failed to run secret-tool: {}", e);
+            return None;
+        }
+        Err(_) => {
+            warn!("Timed out reading keyring password for {}", app);
+            return None;
+        }
+    };
+
+    let password = String::from_utf8(output.stdout).ok()?.trim().to_owned();
+    if !output.status.success() || password.is_empty() {
+        debug!("No keyring password found for {}", app);
+        return None;
+    }
+
+    Some(password)
+}
+
+fn derive_key(password: &[u8]) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    pbkdf2_hmac::<Sha1>(password, SALT, 1, &mut key);
+    key
+}
+
+/// 解密 Linux 上 Chromium 的 v10、v11 cookie
+pub(super) struct Decryptor {
+    v10_key: [u8; 16],
+    /// 密钥环中的密码和空密码，密钥环不可用时 Chromium 会使用空密码
+    v11_keys: Vec<[u8; 16]>,
+    strip_host_hash: bool,
+}
+
+impl Decryptor {
+    pub(super) fn new(keyring_password: Option<&str>, db_version: i64) -> Self {
+        let v11_keys = keyring_password
+            .map(|password| derive_key(password.as_bytes()))
+            .into_iter()
+            .chain(std::iter::once(derive_key(b"")))
+            .collect();
+
+        Decryptor {
+            v10_key: derive_key(V10_PASSWORD),
+            v11_keys,
+            strip_host_hash: db_version >= HOST_HASH_VERSION,
+        }
+    }
+
+    pub(super) fn decrypt(&self, encrypted: &[u8]) -> Option<String> {
+        let (keys, data) = match encrypted.split_at_checked(3) {
+            Some((b"v10", data)) => (std::slice::from_ref(&self.v10_key), data),
+            Some((b"v11", data)) => (self.v11_keys.as_slice(), data),
+            _ => {
+                trace!("Unsupported cookie encryption");
+                return None;
+            }
+        };
+
+        keys.iter().find_map(|key| {
+            let plain = Aes128CbcDec::new(key.into(), &IV.into())
+                .decrypt_padded_vec_mut::<Pkcs7>(data)
+                .ok()?;
+            let plain = if self.strip_host_hash {
+                plain.get(HOST_HASH_LEN..)?
+            } else {
+                &plain
+            };
+            String::from_utf8(plain.to_vec()).ok()
+        })
+    }
+}
+
+pub(super) async fn db_version(conn: &mut SqliteConnection) -> LsarResult<i64> {
+    let version: Option<(String,)> = sqlx::query_as("SELECT value FROM meta WHERE key = 'version'")
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(version.and_then(|(v,)| v.parse().ok()).unwrap_or(0))
+}
+
+/// 未加密的值直接使用，无法解密的 cookie 会被忽略，按最近访问时间从新到旧排列
+pub(super) async fn read_cookies(
+    conn: &mut SqliteConnection,
+    decryptor: &Decryptor,
+) -> LsarResult<Vec<BrowserCookie>> {
+    let sql = format!(
+        "SELECT host_key, name, value, encrypted_value FROM cookies WHERE {} ORDER BY last_access_utc DESC",
+        domain_condition("host_key")
+    );
+
+    let rows: Vec<(String, String, String, Vec<u8>)> =
+        sqlx::query_as(&sql).fetch_all(conn).await.map_err(|e| {
+            error!("Failed to query Chromium cookies: {:?}", e);
+            e
+        })?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(host, name, value, encrypted)| {
+            let value = if value.is_empty() && !encrypted.is_empty() {
+                decryptor.decrypt(&encrypted).or_else(|| {
+                    debug!("Failed to decrypt cookie {} of {}", name, host);
+                    None
+                })?
+            } else {
+                value
+            };
+            Some(BrowserCookie { host, name, value })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::open_database;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_cookies() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/browser_cookies/chromium/Default/Cookies");
+        let mut conn = open_database(&path).await.unwrap();
+
+        let version = db_version(&mut conn).await.unwrap();
+        assert_eq!(version, 24);
+
+        // 测试数据库中 v11 的值使用此密码加密
+        let decryptor = Decryptor::new(Some("lsar-test-keyring"), version);
+        let cookies = read_cookies(&mut conn, &decryptor).await.unwrap();
+        let values: Vec<_> = cookies
+            .iter()
+            .map(|c| format!("{} {}={}", c.host, c.name, c.value))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ".douyin.com sessionid=chromium-sessionid-v10",
+                ".douyin.com ttwid=chromium-ttwid-v11",
+                ".douyin.com passport_csrf_token=plain-csrf-token",
+                ".bilibili.com buvid3=chromium-buvid3",
+            ]
+        );
+
+        // 没有密钥环密码时 v11 的值无法解密
+        let decryptor = Decryptor::new(None, version);
+        let cookies = read_cookies(&mut conn, &decryptor).await.unwrap();
+        assert!(cookies.iter().all(|c| c.name != "ttwid"));
+    }
+}
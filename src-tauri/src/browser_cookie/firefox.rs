@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use sqlx::SqliteConnection;
+
+use crate::error::LsarResult;
+
+use super::{domain_condition, BrowserCookie};
+
+/// Firefox 各配置所在的目录
+pub(super) fn profile_roots() -> Vec<PathBuf> {
+    let root = if cfg!(target_os = "linux") {
+        dirs::home_dir().map(|home| home.join(".mozilla/firefox"))
+    } else if cfg!(target_os = "macos") {
+        dirs::data_dir().map(|data| data.join("Firefox/Profiles"))
+    } else {
+        dirs::data_dir().map(|data| data.join("Mozilla/Firefox/Profiles"))
+    };
+
+    root.into_iter().collect()
+}
+
+/// Firefox 的 cookie 以明文保存，按最近访问时间从新到旧排列
+pub(super) async fn read_cookies(conn: &mut SqliteConnection) -> LsarResult<Vec<BrowserCookie>> {
+    let sql = format!(
+        "SELECT host, name, value FROM moz_cookies WHERE {} ORDER BY lastAccessed DESC",
+        domain_condition("host")
+    );
+
+    let rows: Vec<(String, String, String)> =
+        sqlx::query_as(&sql).fetch_all(conn).await.map_err(|e| {
+            error!("Failed to query Firefox cookies: {:?}", e);
+            e
+        })?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(host, name, value)| BrowserCookie { host, name, value })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::open_database;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_cookies() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/browser_cookies/firefox/cookies.sqlite");
+        let mut conn = open_database(&path).await.unwrap();
+
+        let cookies = read_cookies(&mut conn).await.unwrap();
+        let names: Vec<_> = cookies
+            .iter()
+            .map(|c| format!("{} {}", c.host, c.name))
+            .collect();
+        // 其他网站的 cookie 不会被读取
+        assert_eq!(
+            names,
+            [
+                ".bilibili.com SESSDATA",
+                ".bilibili.com bili_jct",
+                ".bilibili.com DedeUserID",
+                ".huya.com yyuid",
+                "www.huya.com udb_passport",
+                ".bilibili.com SESSDATA",
+            ]
+        );
+        assert_eq!(cookies[0].value, "firefox-sessdata%2C1775000000%2Cabc");
+    }
+}
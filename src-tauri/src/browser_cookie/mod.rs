@@ -0,0 +1,322 @@
+//! 从本机浏览器的 cookie 数据库中导入直播平台的 cookie
+
+mod chromium;
+mod firefox;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Serialize;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+
+use crate::config::{read_config_file, write_config_file};
+use crate::error::{LsarError, LsarResult};
+use crate::platform::Platform;
+
+use self::chromium::{Decryptor, Keyring};
+
+/// 导入 cookie 的平台、域名和登录后才有的 cookie
+const PLATFORM_DOMAINS: [(Platform, &str, &str); 3] = [
+    (Platform::Bilibili, "bilibili.com", "SESSDATA"),
+    (Platform::Douyin, "douyin.com", "sessionid"),
+    (Platform::Huya, "huya.com", "yyuid"),
+];
+
+#[derive(Debug)]
+struct BrowserCookie {
+    host: String,
+    name: String,
+    value: String,
+}
+
+#[derive(Debug)]
+enum BrowserKind {
+    Firefox,
+    Chromium { keyring: Keyring },
+}
+
+/// 浏览器一个配置中的 cookie 数据库
+#[derive(Debug)]
+struct CookieStore {
+    browser: &'static str,
+    kind: BrowserKind,
+    path: PathBuf,
+}
+
+impl CookieStore {
+    async fn read(&self) -> LsarResult<Vec<BrowserCookie>> {
+        let mut conn = open_database(&self.path).await?;
+
+        match &self.kind {
+            BrowserKind::Firefox => firefox::read_cookies(&mut conn).await,
+            BrowserKind::Chromium { keyring } => {
+                let version = chromium::db_version(&mut conn).await?;
+                let password = keyring.password().await;
+                let decryptor = Decryptor::new(password.as_deref(), version);
+                chromium::read_cookies(&mut conn, &decryptor).await
+            }
+        }
+    }
+}
+
+/// 导入结果
+#[derive(Debug, Serialize)]
+pub struct ImportedCookie {
+    platform: Platform,
+    browser: &'static str,
+    count: usize,
+}
+
+/// 浏览器运行时会锁定数据库，以不可变方式打开，只读取不写入
+async fn open_database(path: &Path) -> LsarResult<SqliteConnection> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .immutable(true);
+
+    SqliteConnection::connect_with(&options).await.map_err(|e| {
+        error!("Failed to open cookie database {}: {:?}", path.display(), e);
+        e.into()
+    })
+}
+
+/// 所有平台域名的查询条件
+fn domain_condition(column: &str) -> String {
+    PLATFORM_DOMAINS
+        .iter()
+        .map(|(_, domain, _)| format!("{} LIKE '%{}'", column, domain))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let host = host.trim_start_matches('.');
+    host == domain || host.strip_suffix(domain).is_some_and(|h| h.ends_with('.'))
+}
+
+/// 平台域名下的 cookie，同名时使用最近访问的，未登录时返回 `None`
+fn cookie_header(
+    cookies: &[BrowserCookie],
+    domain: &str,
+    login_name: &str,
+) -> Option<(String, usize)> {
+    let mut pairs: Vec<(&str, &str)> = Vec::new();
+    for cookie in cookies.iter().filter(|c| domain_matches(&c.host, domain)) {
+        if !cookie.value.is_empty() && pairs.iter().all(|(name, _)| *name != cookie.name) {
+            pairs.push((&cookie.name, &cookie.value));
+        }
+    }
+
+    if pairs.iter().all(|(name, _)| *name != login_name) {
+        return None;
+    }
+
+    let header = pairs
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("; ");
+    Some((header, pairs.len()))
+}
+
+/// 子目录中存在的 cookie 数据库
+fn find_in_profiles(
+    root: &Path,
+    is_profile: impl Fn(&str) -> bool,
+    files: &[&str],
+) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_str().is_some_and(&is_profile))
+        .filter_map(|entry| {
+            files
+                .iter()
+                .map(|file| entry.path().join(file))
+                .find(|path| path.is_file())
+        })
+        .collect()
+}
+
+/// 本机所有浏览器配置中的 cookie 数据库，最近使用的在前
+fn find_stores() -> Vec<CookieStore> {
+    let mut stores = Vec::new();
+
+    for root in firefox::profile_roots() {
+        for path in find_in_profiles(&root, |_| true, &["cookies.sqlite"]) {
+            stores.push(CookieStore {
+                browser: "Firefox",
+                kind: BrowserKind::Firefox,
+                path,
+            });
+        }
+    }
+
+    for (browser, keyring_app, root) in chromium::user_data_dirs() {
+        let is_profile = |name: &str| name == "Default" || name.starts_with("Profile ");
+        for path in find_in_profiles(&root, is_profile, &["Network/Cookies", "Cookies"]) {
+            stores.push(CookieStore {
+                browser,
+                kind: BrowserKind::Chromium {
+                    keyring: Keyring::App(keyring_app),
+                },
+                path,
+            });
+        }
+    }
+
+    let modified = |store: &CookieStore| {
+        fs::metadata(&store.path)
+            .and_then(|m| m.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    };
+    stores.sort_by_key(|store| std::cmp::Reverse(modified(store)));
+
+    debug!("Found cookie databases: {:?}", stores);
+    stores
+}
+
+/// 每个平台使用第一个已登录该平台的浏览器配置中的 cookie
+async fn collect_cookies(stores: &[CookieStore]) -> Vec<(ImportedCookie, String)> {
+    let mut imported: Vec<(ImportedCookie, String)> = Vec::new();
+
+    for store in stores {
+        if imported.len() == PLATFORM_DOMAINS.len() {
+            break;
+        }
+
+        let cookies = match store.read().await {
+            Ok(cookies) => cookies,
+            Err(e) => {
+                warn!("Skipping cookie database {}: {}", store.path.display(), e);
+                continue;
+            }
+        };
+
+        for (platform, domain, login_name) in PLATFORM_DOMAINS {
+            if imported.iter().any(|(i, _)| i.platform == platform) {
+                continue;
+            }
+
+            if let Some((header, count)) = cookie_header(&cookies, domain, login_name) {
+                info!(
+                    "Found {} cookies of {} in {}",
+                    count,
+                    platform.to_str(),
+                    store.browser
+                );
+                imported.push((
+                    ImportedCookie {
+                        platform,
+                        browser: store.browser,
+                        count,
+                    },
+                    header,
+                ));
+            }
+        }
+    }
+
+    imported
+}
+
+/// 从本机浏览器导入 B 站、抖音、虎牙的 cookie 并保存到配置文件
+#[tauri::command]
+pub async fn import_browser_cookies() -> LsarResult<Vec<ImportedCookie>> {
+    info!("Importing cookies from local browsers");
+
+    let stores = find_stores();
+    let imported = collect_cookies(&stores).await;
+    if imported.is_empty() {
+        info!("No logged in cookies found in {} databases", stores.len());
+        return Err(LsarError::Other(
+            "未在本机浏览器中找到已登录直播平台的 cookie".to_owned(),
+        ));
+    }
+
+    let mut config = read_config_file().await?;
+    let mut result = Vec::new();
+    for (item, cookie) in imported {
+        config.set_cookie(item.platform, cookie);
+        result.push(item);
+    }
+    write_config_file(config).await?;
+
+    info!("Imported cookies of {} platforms", result.len());
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(path: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/browser_cookies")
+            .join(path)
+    }
+
+    #[test]
+    fn test_domain_matches() {
+        assert!(domain_matches(".bilibili.com", "bilibili.com"));
+        assert!(domain_matches("live.bilibili.com", "bilibili.com"));
+        assert!(domain_matches("bilibili.com", "bilibili.com"));
+        assert!(!domain_matches("notbilibili.com", "bilibili.com"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_cookies() {
+        let stores = [
+            CookieStore {
+                browser: "Firefox",
+                kind: BrowserKind::Firefox,
+                path: fixture("firefox/cookies.sqlite"),
+            },
+            CookieStore {
+                browser: "Chromium",
+                // 测试数据库中 v11 的值使用此密码加密
+                kind: BrowserKind::Chromium {
+                    keyring: Keyring::Password("lsar-test-keyring"),
+                },
+                path: fixture("chromium/Default/Cookies"),
+            },
+        ];
+        assert_eq!(
+            find_in_profiles(
+                &fixture("chromium"),
+                |name| name == "Default",
+                &["Network/Cookies", "Cookies"]
+            ),
+            [fixture("chromium/Default/Cookies")]
+        );
+
+        let imported = collect_cookies(&stores).await;
+        let summary: Vec<_> = imported
+            .iter()
+            .map(|(i, cookie)| (i.platform, i.browser, cookie.as_str()))
+            .collect();
+
+        // Chromium 中的 B 站 cookie 未登录
+        assert_eq!(
+            summary,
+            [
+                (
+                    Platform::Bilibili,
+                    "Firefox",
+                    "SESSDATA=firefox-sessdata%2C1775000000%2Cabc; bili_jct=firefox-bili-jct; DedeUserID=434334701"
+                ),
+                (Platform::Huya, "Firefox", "yyuid=1234567890; udb_passport=hy_test"),
+                (
+                    Platform::Douyin,
+                    "Chromium",
+                    "sessionid=chromium-sessionid-v10; ttwid=chromium-ttwid-v11; passport_csrf_token=plain-csrf-token"
+                ),
+            ]
+        );
+    }
+}
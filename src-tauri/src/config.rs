@@ -1,9 +1,9 @@
-use std::{path::PathBuf, process::Command, sync::LazyLock};
+use std::{collections::BTreeMap, path::PathBuf, process::Command, sync::LazyLock, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 
-use crate::{error::LsarResult, global::APP_CONFIG_DIR};
+use crate::{error::LsarResult, global::APP_CONFIG_DIR, platform::Platform as PlatformKind};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct Player {
@@ -16,18 +16,123 @@ struct Bilibili {
     cookie: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct Douyin {
+    cookie: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct Huya {
+    cookie: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct Platform {
     bilibili: Bilibili,
+    #[serde(default)]
+    douyin: Douyin,
+    #[serde(default)]
+    huya: Huya,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Eval {
+    /// 等待 webview 返回 js 执行结果的时间，单位秒
+    timeout: u64,
+}
+
+impl Default for Eval {
+    fn default() -> Self {
+        Eval { timeout: 10 }
+    }
+}
+
+/// 后台查询收藏房间直播状态的设置
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct Monitor {
+    enabled: bool,
+    /// 两轮查询之间的间隔，单位秒
+    interval: u64,
+    /// 每轮间隔随机增加的最长时间，单位秒
+    jitter: u64,
+    /// 同一平台两次请求之间的最小间隔，单位毫秒
+    request_interval: u64,
+    /// 按平台覆盖 `request_interval`，键为平台名
+    platform_request_intervals: BTreeMap<String, u64>,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Monitor {
+            enabled: true,
+            interval: 180,
+            jitter: 60,
+            request_interval: 1500,
+            // 抖音对频繁请求较敏感
+            platform_request_intervals: BTreeMap::from([("douyin".to_owned(), 5000)]),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
     player: Player,
     platform: Platform,
+    #[serde(default)]
+    eval: Eval,
+    #[serde(default)]
+    monitor: Monitor,
 }
 
 impl Config {
+    pub fn eval_timeout(&self) -> Duration {
+        Duration::from_secs(self.eval.timeout)
+    }
+
+    pub fn monitor_enabled(&self) -> bool {
+        self.monitor.enabled
+    }
+
+    pub fn monitor_interval(&self) -> Duration {
+        Duration::from_secs(self.monitor.interval)
+    }
+
+    pub fn monitor_jitter(&self) -> Duration {
+        Duration::from_secs(self.monitor.jitter)
+    }
+
+    pub fn request_interval(&self, platform: PlatformKind) -> Duration {
+        let millis = self
+            .monitor
+            .platform_request_intervals
+            .get(platform.to_str())
+            .copied()
+            .unwrap_or(self.monitor.request_interval);
+        Duration::from_millis(millis)
+    }
+
+    pub fn bilibili_cookie(&self) -> &str {
+        &self.platform.bilibili.cookie
+    }
+
+    pub fn douyin_cookie(&self) -> &str {
+        &self.platform.douyin.cookie
+    }
+
+    pub fn huya_cookie(&self) -> &str {
+        &self.platform.huya.cookie
+    }
+
+    pub fn set_cookie(&mut self, platform: PlatformKind, cookie: String) {
+        match platform {
+            PlatformKind::Bilibili => self.platform.bilibili.cookie = cookie,
+            PlatformKind::Douyin => self.platform.douyin.cookie = cookie,
+            PlatformKind::Huya => self.platform.huya.cookie = cookie,
+            _ => warn!("No cookie setting for platform: {}", platform.to_str()),
+        }
+    }
+
     pub fn play(&self, url: String) -> LsarResult<()> {
         debug!("Attempting to play URL: {}", url);
         let result = Command::new(&self.player.path)
@@ -0,0 +1,305 @@
+//! 数据库结构的版本迁移，已发布的迁移不能再修改，结构变化时只能追加新的迁移
+
+use super::SqlitePool;
+use crate::error::{LsarError, LsarResult};
+
+/// 按版本顺序排列，第 n 个迁移将数据库升级到版本 n。
+/// 引入版本号之前创建的数据库版本为 0，前两个迁移使用 `IF NOT EXISTS` 兼容已有的表。
+/// 迁移中的平台编号是旧版本 `Platform` 的取值，不能随注册表改变
+const MIGRATIONS: &[&str] = &[
+    // v1：播放历史
+    "CREATE TABLE IF NOT EXISTS history (
+        id              INTEGER PRIMARY KEY,
+        platform        INTEGER NOT NULL,
+        room_id         INTEGER NOT NULL,
+        anchor          TEXT NOT NULL,
+        category        TEXT NOT NULL,
+        last_title      TEXT NOT NULL,
+        last_play_time  DATETIME NOT NULL
+    );
+    CREATE UNIQUE INDEX IF NOT EXISTS idx_unique_platform_room_id ON history (platform, room_id);",
+    // v2：收藏
+    "CREATE TABLE IF NOT EXISTS favorites (
+        id              INTEGER PRIMARY KEY,
+        platform        INTEGER NOT NULL,
+        room_id         TEXT NOT NULL,
+        anchor          TEXT NOT NULL,
+        alias           TEXT NOT NULL DEFAULT '',
+        tags            TEXT NOT NULL DEFAULT '[]',
+        sort_order      INTEGER NOT NULL,
+        notify_live     BOOLEAN NOT NULL DEFAULT 1,
+        notify_offline  BOOLEAN NOT NULL DEFAULT 0,
+        notify_title    BOOLEAN NOT NULL DEFAULT 0,
+        created_at      DATETIME NOT NULL,
+        UNIQUE (platform, room_id)
+    );",
+    // v3：平台保存为字符串键，房间号保存为文本，未知的平台编号原样转为文本，读取时跳过
+    "CREATE TABLE history_v3 (
+        id              INTEGER PRIMARY KEY,
+        platform        TEXT NOT NULL,
+        room_id         TEXT NOT NULL,
+        anchor          TEXT NOT NULL,
+        category        TEXT NOT NULL,
+        last_title      TEXT NOT NULL,
+        last_play_time  DATETIME NOT NULL
+    );
+    INSERT INTO history_v3 (id, platform, room_id, anchor, category, last_title, last_play_time)
+        SELECT id, CASE platform
+                WHEN 0 THEN 'douyu' WHEN 1 THEN 'huya' WHEN 2 THEN 'douyin'
+                WHEN 3 THEN 'bilibili' WHEN 4 THEN 'kuaishou' WHEN 5 THEN 'cc'
+                WHEN 6 THEN 'twitch' WHEN 7 THEN 'youtube' WHEN 8 THEN 'acfun'
+                WHEN 9 THEN 'generic' ELSE CAST(platform AS TEXT) END,
+            CAST(room_id AS TEXT), anchor, category, last_title, last_play_time
+        FROM history;
+    DROP TABLE history;
+    ALTER TABLE history_v3 RENAME TO history;
+    CREATE UNIQUE INDEX idx_unique_platform_room_id ON history (platform, room_id);
+
+    CREATE TABLE favorites_v3 (
+        id              INTEGER PRIMARY KEY,
+        platform        TEXT NOT NULL,
+        room_id         TEXT NOT NULL,
+        anchor          TEXT NOT NULL,
+        alias           TEXT NOT NULL DEFAULT '',
+        tags            TEXT NOT NULL DEFAULT '[]',
+        sort_order      INTEGER NOT NULL,
+        notify_live     BOOLEAN NOT NULL DEFAULT 1,
+        notify_offline  BOOLEAN NOT NULL DEFAULT 0,
+        notify_title    BOOLEAN NOT NULL DEFAULT 0,
+        created_at      DATETIME NOT NULL,
+        UNIQUE (platform, room_id)
+    );
+    INSERT INTO favorites_v3
+        SELECT id, CASE platform
+                WHEN 0 THEN 'douyu' WHEN 1 THEN 'huya' WHEN 2 THEN 'douyin'
+                WHEN 3 THEN 'bilibili' WHEN 4 THEN 'kuaishou' WHEN 5 THEN 'cc'
+                WHEN 6 THEN 'twitch' WHEN 7 THEN 'youtube' WHEN 8 THEN 'acfun'
+                WHEN 9 THEN 'generic' ELSE CAST(platform AS TEXT) END,
+            room_id, anchor, alias, tags, sort_order,
+            notify_live, notify_offline, notify_title, created_at
+        FROM favorites;
+    DROP TABLE favorites;
+    ALTER TABLE favorites_v3 RENAME TO favorites;",
+];
+
+pub(super) async fn migrate(pool: &SqlitePool) -> LsarResult<()> {
+    apply(pool, MIGRATIONS).await
+}
+
+/// 在一个事务中执行所有未执行的迁移，任一迁移失败时数据库保持原样
+async fn apply(pool: &SqlitePool, migrations: &[&str]) -> LsarResult<()> {
+    let latest = migrations.len() as i64;
+    let mut tx = pool.begin().await.map_err(|e| {
+        error!("Failed to begin migration transaction: {:?}", e);
+        e
+    })?;
+
+    sqlx::query("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Failed to create schema_version table: {:?}", e);
+            e
+        })?;
+
+    let version: Option<(i64,)> = sqlx::query_as("SELECT version FROM schema_version")
+        .fetch_optional(&mut *tx)
+        .await?;
+    let version = version.map(|(v,)| v).unwrap_or(0);
+    debug!("Current database version: {}", version);
+
+    // 降级后继续读写不认识的结构可能损坏数据
+    if version > latest {
+        error!(
+            "Database version {} is newer than supported version {}",
+            version, latest
+        );
+        return Err(LsarError::Other(format!(
+            "数据库版本 {} 高于当前程序支持的版本 {}，请使用新版本的程序",
+            version, latest
+        )));
+    }
+    if version == latest {
+        return Ok(());
+    }
+
+    for (index, sql) in migrations.iter().enumerate().skip(version as usize) {
+        let target = index as i64 + 1;
+        sqlx::query(sql).execute(&mut *tx).await.map_err(|e| {
+            error!("Failed to migrate database to version {}: {:?}", target, e);
+            e
+        })?;
+        info!("Migrated database to version {}", target);
+    }
+
+    sqlx::query("DELETE FROM schema_version")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("INSERT INTO schema_version (version) VALUES (?)")
+        .bind(latest)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await.map_err(|e| {
+        error!("Failed to commit migration transaction: {:?}", e);
+        e
+    })?;
+    info!("Database migrated to version {}", latest);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    const LATEST: i64 = MIGRATIONS.len() as i64;
+
+    /// 引入版本号之前的数据库结构和数据
+    const SCHEMA_V1: &str = include_str!("../../tests/fixtures/db/schema_v1.sql");
+
+    async fn empty_pool() -> SqlitePool {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    async fn version(pool: &SqlitePool) -> i64 {
+        sqlx::query_as::<_, (i64,)>("SELECT version FROM schema_version")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+            .0
+    }
+
+    async fn tables(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_as::<_, (String,)>(
+            "SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(name,)| name)
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn test_migrate_new_database() {
+        let pool = empty_pool().await;
+        migrate(&pool).await.unwrap();
+
+        assert_eq!(version(&pool).await, LATEST);
+        assert_eq!(
+            tables(&pool).await,
+            ["favorites", "history", "schema_version"]
+        );
+
+        // 一个迁移中的多条语句都会执行
+        let index: Option<(String,)> = sqlx::query_as(
+            "SELECT name FROM sqlite_master WHERE name = 'idx_unique_platform_room_id'",
+        )
+        .fetch_optional(&pool)
+        .await
+        .unwrap();
+        assert!(index.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_v1_database() {
+        let pool = empty_pool().await;
+        sqlx::raw_sql(SCHEMA_V1).execute(&pool).await.unwrap();
+
+        migrate(&pool).await.unwrap();
+        assert_eq!(version(&pool).await, LATEST);
+
+        let rooms: Vec<(String, String, String)> =
+            sqlx::query_as("SELECT platform, room_id, typeof(room_id) FROM history ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        let text = |platform: &str, room_id: &str| {
+            (platform.to_owned(), room_id.to_owned(), "text".to_owned())
+        };
+        assert_eq!(
+            rooms,
+            [
+                text("douyu", "9999"),
+                text("bilibili", "21452505"),
+                text("huya", "660000"),
+                // 更新版本的程序写入的未知平台保留原值
+                text("42", "123"),
+            ]
+        );
+
+        // 再次执行不会重复迁移
+        migrate(&pool).await.unwrap();
+        assert_eq!(version(&pool).await, LATEST);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_v2_favorites() {
+        let pool = empty_pool().await;
+        apply(&pool, &MIGRATIONS[..2]).await.unwrap();
+        sqlx::query(
+            "INSERT INTO favorites (platform, room_id, anchor, alias, tags, sort_order, notify_title, created_at)
+             VALUES (6, 'teststreamer', '主播', '备注', '[\"英文\"]', 3, 1, '2024-09-01 20:00:00.0 +00:00:00')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        migrate(&pool).await.unwrap();
+        let favorite: (String, String, String, String, i64, bool, bool) = sqlx::query_as(
+            "SELECT platform, room_id, alias, tags, sort_order, notify_live, notify_title FROM favorites",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            favorite,
+            (
+                "twitch".to_owned(),
+                "teststreamer".to_owned(),
+                "备注".to_owned(),
+                r#"["英文"]"#.to_owned(),
+                3,
+                true,
+                true
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_newer_database_is_rejected() {
+        let pool = empty_pool().await;
+        migrate(&pool).await.unwrap();
+        sqlx::query("UPDATE schema_version SET version = ?")
+            .bind(LATEST + 1)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(matches!(migrate(&pool).await, Err(LsarError::Other(_))));
+        assert_eq!(version(&pool).await, LATEST + 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_rolls_back() {
+        let pool = empty_pool().await;
+        sqlx::raw_sql(SCHEMA_V1).execute(&pool).await.unwrap();
+
+        let migrations = [
+            MIGRATIONS[0],
+            MIGRATIONS[1],
+            "ALTER TABLE missing ADD x TEXT",
+        ];
+        assert!(apply(&pool, &migrations).await.is_err());
+        // 已执行的迁移和版本表都被回滚
+        assert_eq!(tables(&pool).await, ["history"]);
+    }
+}
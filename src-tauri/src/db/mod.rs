@@ -0,0 +1,546 @@
+mod migrations;
+
+use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite};
+use time::OffsetDateTime;
+use tokio::sync::OnceCell;
+
+use crate::{
+    error::{LsarError, LsarResult},
+    favorite::{FavoriteChanges, FavoriteItem, FavoriteRow, NewFavorite},
+    global::APP_CONFIG_DIR,
+    history::{HistoryItem, HistoryRow},
+    platform::Platform,
+};
+
+use self::migrations::migrate;
+
+type SqlitePool = Pool<Sqlite>;
+
+static SQLITE_POOL: OnceCell<SqlitePool> = OnceCell::const_new();
+
+/// 连接失败或迁移失败时返回错误，下次调用时重试
+async fn get_global_pool() -> LsarResult<&'static SqlitePool> {
+    SQLITE_POOL
+        .get_or_try_init(|| async {
+            let db_path = APP_CONFIG_DIR.join("lsar.db");
+            let uri = format!("{}?mode=rwc", db_path.display());
+
+            info!("Initializing database connection with URI: {}", uri);
+
+            let pool = SqlitePoolOptions::new()
+                .max_connections(5)
+                .connect(&uri)
+                .await
+                .map_err(|e| {
+                    error!("Failed to connect to the database: {:?}", e);
+                    e
+                })?;
+
+            migrate(&pool).await.map_err(|e| {
+                error!("Failed to migrate the database: {}", e);
+                e
+            })?;
+
+            Ok(pool)
+        })
+        .await
+}
+
+async fn query_history(pool: &SqlitePool) -> LsarResult<Vec<HistoryItem>> {
+    let rows: Vec<HistoryRow> = sqlx::query_as(
+        "SELECT id, platform, room_id, anchor, category, last_title, last_play_time
+         FROM history
+         ORDER BY last_play_time DESC;",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch all history records: {:?}", e);
+        e
+    })?;
+
+    info!("Successfully fetched {} history records", rows.len());
+
+    // 更新版本的程序可能写入当前版本不支持的平台
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let id = row.0;
+            row.try_into()
+                .map_err(|e| warn!("Skipping invalid history record {}: {}", id, e))
+                .ok()
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_all_history() -> LsarResult<Vec<HistoryItem>> {
+    debug!("Fetching all history records");
+    query_history(get_global_pool().await?).await
+}
+
+#[tauri::command]
+pub async fn delete_a_history_by_id(id: i64) -> LsarResult<()> {
+    debug!("Attempting to delete history record with id: {}", id);
+
+    let pool = get_global_pool().await?;
+
+    let result = sqlx::query("DELETE FROM history WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete history record: {:?}", e);
+            e
+        })?;
+
+    if result.rows_affected() == 0 {
+        warn!("No history record found with id: {}", id);
+    } else {
+        info!("Successfully deleted history record with id: {}", id);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn insert_a_history(history: HistoryItem) -> LsarResult<()> {
+    upsert_history(&history).await
+}
+
+/// 同一平台的同一房间只保留一条记录，再次播放时更新标题和时间
+pub(crate) async fn upsert_history(history: &HistoryItem) -> LsarResult<()> {
+    debug!(
+        "Inserting or updating history record for platform: {}, room_id: {}",
+        history.platform().to_str(),
+        history.room_id()
+    );
+
+    let pool = get_global_pool().await?;
+
+    let result = sqlx::query(
+        r#"
+    INSERT INTO history (platform, room_id, anchor, category, last_title, last_play_time)
+    VALUES (?, ?, ?, ?, ?, ?)
+    ON CONFLICT(platform, room_id) DO UPDATE SET
+    category = excluded.category,
+    last_title = excluded.last_title,
+    last_play_time = excluded.last_play_time
+    "#,
+    )
+    .bind(history.platform().to_str())
+    .bind(history.room_id())
+    .bind(history.anchor())
+    .bind(history.category())
+    .bind(history.last_title())
+    .bind(history.last_play_time())
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to insert or update history record: {:?}", e);
+        e
+    })?;
+
+    if result.rows_affected() > 0 {
+        info!(
+            "Successfully inserted or updated history record for platform: {}, room_id: {}",
+            history.platform().to_str(),
+            history.room_id()
+        );
+    } else {
+        warn!(
+            "No changes made to history record for platform: {}, room_id: {}",
+            history.platform().to_str(),
+            history.room_id()
+        );
+    }
+
+    Ok(())
+}
+
+const FAVORITE_COLUMNS: &str = "id, platform, room_id, anchor, alias, tags, sort_order, \
+    notify_live, notify_offline, notify_title, created_at";
+
+/// 去掉空白和重复的标签
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !result.iter().any(|t| t == tag) {
+            result.push(tag.to_owned());
+        }
+    }
+    result
+}
+
+fn tags_to_json(tags: &[String]) -> LsarResult<String> {
+    Ok(serde_json::to_string(&normalize_tags(tags))?)
+}
+
+async fn query_favorites(pool: &SqlitePool) -> LsarResult<Vec<FavoriteItem>> {
+    let rows: Vec<FavoriteRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM favorites ORDER BY sort_order, id",
+        FAVORITE_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to fetch favorites: {:?}", e);
+        e
+    })?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let id = row.0;
+            row.try_into()
+                .map_err(|e| warn!("Skipping invalid favorite {}: {}", id, e))
+                .ok()
+        })
+        .collect())
+}
+
+async fn query_favorite(
+    pool: &SqlitePool,
+    platform: Platform,
+    room_id: &str,
+) -> LsarResult<FavoriteItem> {
+    let row: FavoriteRow = sqlx::query_as(&format!(
+        "SELECT {} FROM favorites WHERE platform = ? AND room_id = ?",
+        FAVORITE_COLUMNS
+    ))
+    .bind(platform.to_str())
+    .bind(room_id)
+    .fetch_one(pool)
+    .await?;
+
+    row.try_into().map_err(LsarError::from)
+}
+
+/// 已收藏的房间不会重复添加，返回已有的记录
+async fn insert_favorite(pool: &SqlitePool, favorite: &NewFavorite) -> LsarResult<FavoriteItem> {
+    debug!(
+        "Adding favorite for platform: {}, room_id: {}",
+        favorite.platform.to_str(),
+        favorite.room_id
+    );
+
+    let result = sqlx::query(
+        r#"
+    INSERT INTO favorites (platform, room_id, anchor, alias, tags, sort_order, created_at)
+    VALUES (?, ?, ?, ?, ?, (SELECT COALESCE(MAX(sort_order), -1) + 1 FROM favorites), ?)
+    ON CONFLICT(platform, room_id) DO NOTHING
+    "#,
+    )
+    .bind(favorite.platform.to_str())
+    .bind(&favorite.room_id)
+    .bind(&favorite.anchor)
+    .bind(favorite.alias.trim())
+    .bind(tags_to_json(&favorite.tags)?)
+    .bind(OffsetDateTime::now_utc())
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to insert favorite: {:?}", e);
+        e
+    })?;
+
+    if result.rows_affected() == 0 {
+        info!("Room is already in favorites: {}", favorite.room_id);
+    } else {
+        info!("Successfully added favorite: {}", favorite.room_id);
+    }
+
+    query_favorite(pool, favorite.platform, &favorite.room_id).await
+}
+
+async fn insert_favorite_from_history(
+    pool: &SqlitePool,
+    history_id: i64,
+) -> LsarResult<FavoriteItem> {
+    let row: Option<(String, String, String)> =
+        sqlx::query_as("SELECT platform, room_id, anchor FROM history WHERE id = ?")
+            .bind(history_id)
+            .fetch_optional(pool)
+            .await?;
+
+    let Some((platform, room_id, anchor)) = row else {
+        warn!("No history record found with id: {}", history_id);
+        return Err(LsarError::Other("历史记录不存在".to_owned()));
+    };
+
+    let favorite = NewFavorite {
+        platform: platform.as_str().try_into().map_err(LsarError::from)?,
+        room_id,
+        anchor,
+        alias: String::new(),
+        tags: Vec::new(),
+    };
+    insert_favorite(pool, &favorite).await
+}
+
+/// `ids` 中的顺序即新的排列顺序
+async fn update_sort_order(pool: &SqlitePool, ids: &[i64]) -> LsarResult<()> {
+    let mut tx = pool.begin().await?;
+    for (index, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE favorites SET sort_order = ? WHERE id = ?")
+            .bind(index as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                error!("Failed to update sort order of favorite {}: {:?}", id, e);
+                e
+            })?;
+    }
+    tx.commit().await?;
+
+    info!("Successfully reordered {} favorites", ids.len());
+    Ok(())
+}
+
+async fn update_tags(pool: &SqlitePool, id: i64, tags: &[String]) -> LsarResult<()> {
+    let result = sqlx::query("UPDATE favorites SET tags = ? WHERE id = ?")
+        .bind(tags_to_json(tags)?)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to update tags of favorite {}: {:?}", id, e);
+            e
+        })?;
+
+    if result.rows_affected() == 0 {
+        warn!("No favorite found with id: {}", id);
+    }
+    Ok(())
+}
+
+async fn update_favorite_fields(
+    pool: &SqlitePool,
+    id: i64,
+    changes: &FavoriteChanges,
+) -> LsarResult<()> {
+    let result = sqlx::query(
+        r#"
+    UPDATE favorites SET
+    alias = COALESCE(?, alias),
+    notify_live = COALESCE(?, notify_live),
+    notify_offline = COALESCE(?, notify_offline),
+    notify_title = COALESCE(?, notify_title)
+    WHERE id = ?
+    "#,
+    )
+    .bind(changes.alias.as_deref().map(str::trim))
+    .bind(changes.notify_live)
+    .bind(changes.notify_offline)
+    .bind(changes.notify_title)
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Failed to update favorite {}: {:?}", id, e);
+        e
+    })?;
+
+    if result.rows_affected() == 0 {
+        warn!("No favorite found with id: {}", id);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_favorites() -> LsarResult<Vec<FavoriteItem>> {
+    debug!("Fetching all favorites");
+    query_favorites(get_global_pool().await?).await
+}
+
+#[tauri::command]
+pub async fn add_favorite(favorite: NewFavorite) -> LsarResult<FavoriteItem> {
+    insert_favorite(get_global_pool().await?, &favorite).await
+}
+
+/// 将历史记录中的房间加入收藏
+#[tauri::command]
+pub async fn promote_history_to_favorite(history_id: i64) -> LsarResult<FavoriteItem> {
+    debug!("Promoting history record {} to favorites", history_id);
+    insert_favorite_from_history(get_global_pool().await?, history_id).await
+}
+
+#[tauri::command]
+pub async fn remove_favorite(id: i64) -> LsarResult<()> {
+    debug!("Attempting to delete favorite with id: {}", id);
+
+    let result = sqlx::query("DELETE FROM favorites WHERE id = ?")
+        .bind(id)
+        .execute(get_global_pool().await?)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete favorite: {:?}", e);
+            e
+        })?;
+
+    if result.rows_affected() == 0 {
+        warn!("No favorite found with id: {}", id);
+    } else {
+        info!("Successfully deleted favorite with id: {}", id);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reorder_favorites(ids: Vec<i64>) -> LsarResult<()> {
+    update_sort_order(get_global_pool().await?, &ids).await
+}
+
+#[tauri::command]
+pub async fn set_favorite_tags(id: i64, tags: Vec<String>) -> LsarResult<()> {
+    update_tags(get_global_pool().await?, id, &tags).await
+}
+
+#[tauri::command]
+pub async fn update_favorite(id: i64, changes: FavoriteChanges) -> LsarResult<()> {
+    update_favorite_fields(get_global_pool().await?, id, &changes).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn memory_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrate(&pool).await.unwrap();
+        pool
+    }
+
+    fn new_favorite(platform: Platform, room_id: &str) -> NewFavorite {
+        NewFavorite {
+            platform,
+            room_id: room_id.to_owned(),
+            anchor: format!("{}的主播", room_id),
+            alias: String::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn room_ids(favorites: &[FavoriteItem]) -> Vec<String> {
+        favorites
+            .iter()
+            .map(|f| {
+                serde_json::to_value(f).unwrap()["room_id"]
+                    .as_str()
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_favorites() {
+        let pool = memory_pool().await;
+
+        let douyu = insert_favorite(&pool, &new_favorite(Platform::Douyu, "9999"))
+            .await
+            .unwrap();
+        let twitch = insert_favorite(&pool, &new_favorite(Platform::Twitch, "teststreamer"))
+            .await
+            .unwrap();
+        // 重复添加返回已有的记录
+        let again = insert_favorite(&pool, &new_favorite(Platform::Douyu, "9999"))
+            .await
+            .unwrap();
+        assert_eq!(again, douyu);
+        assert_eq!(
+            room_ids(&query_favorites(&pool).await.unwrap()),
+            ["9999", "teststreamer"]
+        );
+
+        let twitch_id = serde_json::to_value(&twitch).unwrap()["id"]
+            .as_i64()
+            .unwrap();
+        let douyu_id = serde_json::to_value(&douyu).unwrap()["id"]
+            .as_i64()
+            .unwrap();
+        update_sort_order(&pool, &[twitch_id, douyu_id])
+            .await
+            .unwrap();
+        update_tags(
+            &pool,
+            twitch_id,
+            &[
+                " 游戏 ".to_owned(),
+                "".to_owned(),
+                "游戏".to_owned(),
+                "英文".to_owned(),
+            ],
+        )
+        .await
+        .unwrap();
+        update_favorite_fields(
+            &pool,
+            twitch_id,
+            &FavoriteChanges {
+                alias: Some("测试".to_owned()),
+                notify_title: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let favorites = query_favorites(&pool).await.unwrap();
+        assert_eq!(room_ids(&favorites), ["teststreamer", "9999"]);
+        let first = serde_json::to_value(&favorites[0]).unwrap();
+        assert_eq!(first["tags"], serde_json::json!(["游戏", "英文"]));
+        assert_eq!(first["alias"], "测试");
+        assert_eq!(first["notify_live"], true);
+        assert_eq!(first["notify_title"], true);
+        assert_eq!(first["notify_offline"], false);
+    }
+
+    #[tokio::test]
+    async fn test_promote_history() {
+        let pool = memory_pool().await;
+        sqlx::query(
+            "INSERT INTO history (platform, room_id, anchor, category, last_title, last_play_time)
+             VALUES (?, '21452505', '主播', '分类', '标题', ?)",
+        )
+        .bind(Platform::Bilibili.to_str())
+        .bind(OffsetDateTime::now_utc())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let favorite = insert_favorite_from_history(&pool, 1).await.unwrap();
+        let favorite = serde_json::to_value(favorite).unwrap();
+        assert_eq!(favorite["platform"], "bilibili");
+        assert_eq!(favorite["room_id"], "21452505");
+        assert_eq!(favorite["anchor"], "主播");
+
+        assert!(insert_favorite_from_history(&pool, 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_skip_unknown_history() {
+        let pool = memory_pool().await;
+        for platform in ["douyu", "unknown"] {
+            sqlx::query(
+                "INSERT INTO history (platform, room_id, anchor, category, last_title, last_play_time)
+                 VALUES (?, '9999', '主播', '分类', '标题', ?)",
+            )
+            .bind(platform)
+            .bind(OffsetDateTime::now_utc())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let history = query_history(&pool).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(*history[0].platform(), Platform::Douyu);
+    }
+}
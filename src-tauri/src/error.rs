@@ -31,6 +31,8 @@ pub(super) enum LsarError {
     #[error(transparent)]
     MissKeyField(#[from] MissKeyFieldError),
     #[error(transparent)]
+    Input(#[from] InputError),
+    #[error(transparent)]
     UrlParse(#[from] url::ParseError),
     #[error(transparent)]
     Eval(#[from] EvalError),
@@ -107,6 +109,14 @@ pub(super) enum RoomStateError {
     NotExists,
     IsClosed,
     IsReplay,
+    /// 预告的直播尚未开始
+    Upcoming,
+    /// 直播已结束
+    Ended,
+    /// 链接指向的是普通视频
+    NotLive,
+    /// 按主播查询时主播当前没有在直播
+    AnchorOffline,
 }
 
 impl fmt::Display for RoomStateError {
@@ -116,6 +126,10 @@ impl fmt::Display for RoomStateError {
             RoomStateError::NotExists => "房间号不存在",
             RoomStateError::IsClosed => "该房间已被关闭",
             RoomStateError::IsReplay => "该房间正在重播",
+            RoomStateError::Upcoming => "该直播尚未开始",
+            RoomStateError::Ended => "该直播已结束",
+            RoomStateError::NotLive => "该链接不是直播",
+            RoomStateError::AnchorOffline => "该主播当前未开播",
         };
         write!(f, "{}", string)
     }
@@ -142,6 +156,8 @@ pub(super) enum MissKeyFieldError {
     SignatureFunction,
     RandomNumber,
     RoomId,
+    /// 网页中内嵌的状态数据
+    PageState,
 }
 
 impl fmt::Display for MissKeyFieldError {
@@ -150,6 +166,24 @@ impl fmt::Display for MissKeyFieldError {
     }
 }
 
+#[derive(Debug, Serialize, thiserror::Error)]
+pub(super) enum InputError {
+    UnknownPlatform,
+    InvalidRoomId,
+    InvalidUrl,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            InputError::UnknownPlatform => "无法识别直播平台",
+            InputError::InvalidRoomId => "无法从链接中获取房间号",
+            InputError::InvalidUrl => "无效的链接",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 impl From<&str> for LsarError {
     fn from(value: &str) -> Self {
         LsarError::Other(value.to_owned())
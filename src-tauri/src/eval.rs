@@ -1,39 +1,251 @@
-use std::{fmt, sync::Arc};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::{fmt, time::Duration};
 
-use serde::Serialize;
-use tokio::sync::{oneshot, Mutex};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
 
 use crate::error::LsarResult;
 
 #[derive(Debug, Serialize, thiserror::Error)]
 pub enum EvalError {
-    ChannelReceiveError,
-    ChannelSendError(String),
+    /// 等待结果时请求被取消
+    Cancelled,
+    /// 超时时间，单位毫秒
+    Timeout(u64),
+    Emit(String),
+    /// webview 执行脚本时抛出的异常
+    Js(JsException),
+    /// 内置 js 引擎执行脚本时抛出的异常
+    Script(String),
 }
 
 impl fmt::Display for EvalError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            EvalError::Timeout(millis) => {
+                write!(f, "执行 js 超时（{} 秒）", *millis as f64 / 1000.0)
+            }
+            EvalError::Js(e) => write!(f, "{}: {}", e.name, e.message),
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsException {
+    name: String,
+    message: String,
+    stack: Option<String>,
+}
+
 pub const EVAL_EVENT: &str = "JS-EVAL";
 
-pub type EvalSender = Arc<Mutex<Option<oneshot::Sender<String>>>>;
+/// 发送给 webview 的执行请求，结果需要带着 `id` 通过 `eval_result` 返回
+#[derive(Debug, Clone, Serialize)]
+pub struct EvalRequest {
+    id: u64,
+    script: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum EvalResponse {
+    Ok { value: String },
+    Error(JsException),
+}
+
+type Pending = Mutex<HashMap<u64, oneshot::Sender<EvalResponse>>>;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct EvalChannel {
-    pub sender: EvalSender,
+    next_id: Arc<AtomicU64>,
+    pending: Arc<Pending>,
+}
+
+/// 等待结果的一方被丢弃（解析被取消）或超时后，从等待列表中移除对应的请求
+struct PendingGuard<'a> {
+    id: u64,
+    pending: &'a Pending,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        if self.pending.lock().unwrap().remove(&self.id).is_some() {
+            debug!("Eval request {} removed before receiving result", self.id);
+        }
+    }
+}
+
+impl EvalChannel {
+    /// 在 webview 中执行脚本，多个请求可以同时进行
+    pub async fn eval(
+        &self,
+        app_handle: &AppHandle,
+        script: String,
+        timeout: Duration,
+    ) -> LsarResult<String> {
+        self.request(script, timeout, |request| {
+            app_handle
+                .emit(EVAL_EVENT, request)
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    async fn request<F>(&self, script: String, timeout: Duration, emit: F) -> LsarResult<String>
+    where
+        F: FnOnce(&EvalRequest) -> Result<(), String>,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+        let _guard = PendingGuard {
+            id,
+            pending: &self.pending,
+        };
+
+        trace!("Emitting eval request {}", id);
+        emit(&EvalRequest { id, script }).map_err(|e| {
+            error!("Failed to emit eval request {}: {}", id, e);
+            EvalError::Emit(e)
+        })?;
+
+        let response = tokio::time::timeout(timeout, rx)
+            .await
+            .map_err(|_| {
+                warn!("Eval request {} timed out after {:?}", id, timeout);
+                EvalError::Timeout(timeout.as_millis() as u64)
+            })?
+            .map_err(|_| EvalError::Cancelled)?;
+
+        match response {
+            EvalResponse::Ok { value } => {
+                trace!("Eval request {} resolved", id);
+                Ok(value)
+            }
+            EvalResponse::Error(e) => {
+                error!("Eval request {} failed in webview: {:?}", id, e);
+                Err(EvalError::Js(e).into())
+            }
+        }
+    }
+
+    fn resolve(&self, id: u64, response: EvalResponse) {
+        match self.pending.lock().unwrap().remove(&id) {
+            // 接收方已丢弃时无需处理
+            Some(sender) => {
+                let _ = sender.send(response);
+            }
+            None => warn!("Received result for unknown or expired eval request {}", id),
+        }
+    }
 }
 
 #[tauri::command]
 pub async fn eval_result(
-    result: String,
+    id: u64,
+    response: EvalResponse,
     eval_channel: tauri::State<'_, EvalChannel>,
 ) -> LsarResult<()> {
-    if let Some(sender) = eval_channel.sender.lock().await.take() {
-        sender.send(result).map_err(EvalError::ChannelSendError)?;
-        info!("已发送 eval 结果");
-    }
+    eval_channel.resolve(id, response);
+    info!("已返回 eval 结果：{}", id);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    #[tokio::test]
+    async fn test_concurrent_requests() {
+        let channel = EvalChannel::default();
+
+        // 按请求的相反顺序返回结果
+        let responder = channel.clone();
+        let emit = move |request: &EvalRequest| {
+            let responder = responder.clone();
+            let (id, script) = (request.id, request.script.clone());
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(50 - id * 20)).await;
+                responder.resolve(id, EvalResponse::Ok { value: script });
+            });
+            Ok(())
+        };
+
+        let (a, b) = tokio::join!(
+            channel.request("a".to_owned(), TIMEOUT, emit.clone()),
+            channel.request("b".to_owned(), TIMEOUT, emit),
+        );
+        assert_eq!(a.unwrap(), "a");
+        assert_eq!(b.unwrap(), "b");
+        assert!(channel.pending.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_js_error() {
+        let channel = EvalChannel::default();
+        let responder = channel.clone();
+
+        let result = channel
+            .request("throw".to_owned(), TIMEOUT, |request| {
+                responder.resolve(
+                    request.id,
+                    EvalResponse::Error(JsException {
+                        name: "ReferenceError".to_owned(),
+                        message: "CryptoJS is not defined".to_owned(),
+                        stack: None,
+                    }),
+                );
+                Ok(())
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::error::LsarError::Eval(EvalError::Js(e))) if e.name == "ReferenceError"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_and_emit_error() {
+        let channel = EvalChannel::default();
+
+        let result = channel
+            .request("1".to_owned(), Duration::from_millis(10), |_| Ok(()))
+            .await;
+        assert!(matches!(
+            &result,
+            Err(crate::error::LsarError::Eval(EvalError::Timeout(10)))
+        ));
+        assert_eq!(
+            EvalError::Timeout(10).to_string(),
+            "执行 js 超时（0.01 秒）"
+        );
+        assert_eq!(
+            EvalError::Timeout(10_000).to_string(),
+            "执行 js 超时（10 秒）"
+        );
+
+        let result = channel
+            .request("1".to_owned(), TIMEOUT, |_| Err("no window".to_owned()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::error::LsarError::Eval(EvalError::Emit(_)))
+        ));
+        assert!(channel.pending.lock().unwrap().is_empty());
+
+        // 超时之后才到达的结果会被忽略
+        channel.resolve(
+            0,
+            EvalResponse::Ok {
+                value: String::new(),
+            },
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::platform::Platform;
+
+/// 数据库中一行收藏记录的列
+pub(crate) type FavoriteRow = (
+    i64,
+    String,
+    String,
+    String,
+    String,
+    String,
+    i64,
+    bool,
+    bool,
+    bool,
+    OffsetDateTime,
+);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FavoriteItem {
+    pub(crate) id: i64,
+    pub(crate) platform: Platform,
+    pub(crate) room_id: String,
+    anchor: String,
+    /// 用户设置的备注名，为空时显示主播名
+    alias: String,
+    tags: Vec<String>,
+    sort_order: i64,
+    /// 开播时通知
+    pub(crate) notify_live: bool,
+    /// 下播时通知
+    pub(crate) notify_offline: bool,
+    /// 直播标题变化时通知
+    pub(crate) notify_title: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+impl TryFrom<FavoriteRow> for FavoriteItem {
+    type Error = &'static str;
+
+    fn try_from(
+        (
+            id,
+            platform,
+            room_id,
+            anchor,
+            alias,
+            tags,
+            sort_order,
+            notify_live,
+            notify_offline,
+            notify_title,
+            created_at,
+        ): FavoriteRow,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id,
+            platform: platform.as_str().try_into()?,
+            room_id,
+            anchor,
+            alias,
+            tags: serde_json::from_str(&tags).map_err(|_| "Invalid favorite tags")?,
+            sort_order,
+            notify_live,
+            notify_offline,
+            notify_title,
+            created_at,
+        })
+    }
+}
+
+impl FavoriteItem {
+    /// 优先使用备注名
+    pub(crate) fn display_name(&self) -> &str {
+        if self.alias.is_empty() {
+            &self.anchor
+        } else {
+            &self.alias
+        }
+    }
+}
+
+/// 新增收藏时前端传入的字段
+#[derive(Debug, Deserialize)]
+pub struct NewFavorite {
+    pub(crate) platform: Platform,
+    pub(crate) room_id: String,
+    pub(crate) anchor: String,
+    #[serde(default)]
+    pub(crate) alias: String,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+}
+
+/// 修改收藏的备注和通知设置，未传入的字段保持不变
+#[derive(Debug, Default, Deserialize)]
+pub struct FavoriteChanges {
+    pub(crate) alias: Option<String>,
+    pub(crate) notify_live: Option<bool>,
+    pub(crate) notify_offline: Option<bool>,
+    pub(crate) notify_title: Option<bool>,
+}
@@ -1,11 +1,14 @@
+mod browser_cookie;
 mod config;
 mod db;
 mod error;
 mod eval;
+mod favorite;
 mod global;
 mod history;
 mod http;
 mod log;
+mod monitor;
 mod parser;
 mod path;
 mod platform;
@@ -21,13 +24,20 @@ use std::env;
 
 use tauri::{AppHandle, Manager};
 
+use crate::browser_cookie::import_browser_cookies;
 use crate::config::{read_config_file, write_config_file};
-use crate::db::{delete_a_history_by_id, get_all_history, insert_a_history};
+use crate::db::{
+    add_favorite, delete_a_history_by_id, get_all_history, insert_a_history, list_favorites,
+    promote_history_to_favorite, remove_favorite, reorder_favorites, set_favorite_tags,
+    update_favorite,
+};
 use crate::error::LsarResult;
 use crate::eval::eval_result;
 use crate::http::{get, post};
 use crate::log::{debug, error, info, trace, warn};
-use crate::parser::{parse_bilibili, parse_douyin, parse_douyu, parse_huya};
+use crate::parser::{
+    check_live_status, get_bilibili_login_qrcode, list_platforms, parse_url, poll_bilibili_login,
+};
 use crate::path::get_player_paths;
 use crate::setup::{setup_app, setup_logging};
 use crate::utils::md5;
@@ -74,12 +84,20 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(setup_app)
         .invoke_handler(tauri::generate_handler![
             show_main_window,
             get_all_history,
             insert_a_history,
             delete_a_history_by_id,
+            list_favorites,
+            add_favorite,
+            promote_history_to_favorite,
+            remove_favorite,
+            reorder_favorites,
+            set_favorite_tags,
+            update_favorite,
             md5,
             trace,
             debug,
@@ -93,10 +111,12 @@ pub fn run() {
             read_config_file,
             write_config_file,
             eval_result,
-            parse_douyu,
-            parse_huya,
-            parse_douyin,
-            parse_bilibili,
+            parse_url,
+            list_platforms,
+            check_live_status,
+            get_bilibili_login_qrcode,
+            poll_bilibili_login,
+            import_browser_cookies,
             get_player_paths
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,373 @@
+//! 后台定时查询收藏房间的直播状态，状态变化时通知前端并发送系统通知
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use tokio::task::JoinSet;
+
+use crate::config::{read_config_file, Config};
+use crate::db::list_favorites;
+use crate::eval::EvalChannel;
+use crate::favorite::FavoriteItem;
+use crate::parser::{room_status, supports_live_status, LiveStatus, ParseContext};
+use crate::platform::Platform;
+
+pub const LIVE_STATUS_EVENT: &str = "LIVE-STATUS";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StatusChange {
+    WentLive,
+    WentOffline,
+    TitleChanged {
+        #[serde(rename = "previousTitle")]
+        previous_title: String,
+    },
+}
+
+impl StatusChange {
+    fn should_notify(&self, favorite: &FavoriteItem) -> bool {
+        match self {
+            StatusChange::WentLive => favorite.notify_live,
+            StatusChange::WentOffline => favorite.notify_offline,
+            StatusChange::TitleChanged { .. } => favorite.notify_title,
+        }
+    }
+
+    fn summary(&self, name: &str) -> String {
+        match self {
+            StatusChange::WentLive => format!("{} 开播了", name),
+            StatusChange::WentOffline => format!("{} 下播了", name),
+            StatusChange::TitleChanged { .. } => format!("{} 修改了直播标题", name),
+        }
+    }
+}
+
+/// 收藏房间直播状态变化时发送给前端的事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveStatusEvent {
+    favorite_id: i64,
+    platform: Platform,
+    #[serde(rename = "roomID")]
+    room_id: String,
+    name: String,
+    title: String,
+    #[serde(flatten)]
+    change: StatusChange,
+}
+
+/// 与上次查询的状态比较，第一次查询到的房间只记录状态
+fn status_change(previous: Option<&LiveStatus>, current: &LiveStatus) -> Option<StatusChange> {
+    let previous = previous?;
+
+    match (previous.live, current.live) {
+        (false, true) => Some(StatusChange::WentLive),
+        (true, false) => Some(StatusChange::WentOffline),
+        (true, true) if !current.title.is_empty() && current.title != previous.title => {
+            Some(StatusChange::TitleChanged {
+                previous_title: previous.title.clone(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// 在间隔上随机增加不超过 `jitter` 的时间，避免每轮请求的时间过于规律
+fn next_delay(interval: Duration, jitter: Duration) -> Duration {
+    let jitter_millis = jitter.as_millis() as u64;
+    if jitter_millis == 0 {
+        return interval;
+    }
+
+    interval + Duration::from_millis(rand::random::<u64>() % (jitter_millis + 1))
+}
+
+/// 不同平台同时查询，同一平台的房间依次查询，两次请求之间至少间隔该平台的限制时间。
+/// 没有轻量状态接口的平台不查询
+async fn check_favorites(
+    favorites: Vec<FavoriteItem>,
+    ctx: Arc<ParseContext>,
+) -> Vec<(FavoriteItem, LiveStatus)> {
+    let mut groups: HashMap<Platform, Vec<FavoriteItem>> = HashMap::new();
+    for favorite in favorites {
+        if !supports_live_status(favorite.platform) {
+            trace!(
+                "Skipping {} room {}: live status is not supported",
+                favorite.platform.to_str(),
+                favorite.room_id
+            );
+            continue;
+        }
+        groups.entry(favorite.platform).or_default().push(favorite);
+    }
+
+    let mut tasks = JoinSet::new();
+    for (platform, favorites) in groups {
+        let ctx = ctx.clone();
+        let request_interval = ctx.config.request_interval(platform);
+
+        tasks.spawn(async move {
+            let mut results = Vec::new();
+            for (index, favorite) in favorites.into_iter().enumerate() {
+                if index > 0 {
+                    tokio::time::sleep(request_interval).await;
+                }
+
+                match room_status(platform, &favorite.room_id, &ctx).await {
+                    Ok(status) => results.push((favorite, status)),
+                    Err(e) => warn!(
+                        "Failed to check live status of {} room {}: {}",
+                        platform.to_str(),
+                        favorite.room_id,
+                        e
+                    ),
+                }
+            }
+            results
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(statuses) => results.extend(statuses),
+            Err(e) => error!("Live status check task failed: {}", e),
+        }
+    }
+    results
+}
+
+struct Monitor {
+    app_handle: AppHandle,
+    eval_channel: EvalChannel,
+    /// 每个收藏上次查询到的状态，键为收藏的 id
+    statuses: HashMap<i64, LiveStatus>,
+}
+
+impl Monitor {
+    /// 查询一轮，返回距下一轮的等待时间。每轮重新读取配置，修改设置后无需重启
+    async fn run_round(&mut self) -> Duration {
+        let config = read_config_file().await.unwrap_or_else(|e| {
+            warn!("Failed to read config for live status monitor: {}", e);
+            Config::default()
+        });
+        let delay = next_delay(config.monitor_interval(), config.monitor_jitter());
+
+        if !config.monitor_enabled() {
+            trace!("Live status monitor is disabled");
+            return delay;
+        }
+
+        let favorites = match list_favorites().await {
+            Ok(favorites) => favorites,
+            Err(e) => {
+                error!("Failed to load favorites for live status monitor: {}", e);
+                return delay;
+            }
+        };
+        self.statuses
+            .retain(|id, _| favorites.iter().any(|f| f.id == *id));
+        if favorites.is_empty() {
+            return delay;
+        }
+
+        debug!("Checking live status of {} favorites", favorites.len());
+        let ctx = Arc::new(ParseContext {
+            config,
+            options: Default::default(),
+            eval_channel: self.eval_channel.clone(),
+            app_handle: self.app_handle.clone(),
+        });
+
+        for (favorite, status) in check_favorites(favorites, ctx).await {
+            if let Some(change) = status_change(self.statuses.get(&favorite.id), &status) {
+                self.notify(&favorite, &status, change);
+            }
+            self.statuses.insert(favorite.id, status);
+        }
+
+        delay
+    }
+
+    fn notify(&self, favorite: &FavoriteItem, status: &LiveStatus, change: StatusChange) {
+        let name = favorite.display_name();
+        info!(
+            "Live status of {} room {} changed: {:?}",
+            favorite.platform.to_str(),
+            favorite.room_id,
+            change
+        );
+
+        if change.should_notify(favorite) {
+            let result = self
+                .app_handle
+                .notification()
+                .builder()
+                .title(change.summary(name))
+                .body(&status.title)
+                .show();
+            if let Err(e) = result {
+                warn!("Failed to show live status notification: {}", e);
+            }
+        }
+
+        let event = LiveStatusEvent {
+            favorite_id: favorite.id,
+            platform: favorite.platform,
+            room_id: favorite.room_id.clone(),
+            name: name.to_owned(),
+            title: status.title.clone(),
+            change,
+        };
+        if let Err(e) = self.app_handle.emit(LIVE_STATUS_EVENT, &event) {
+            error!("Failed to emit live status event: {}", e);
+        }
+    }
+}
+
+pub fn spawn_monitor(app_handle: AppHandle, eval_channel: EvalChannel) {
+    info!("Spawning live status monitor task");
+
+    tauri::async_runtime::spawn(async move {
+        let mut monitor = Monitor {
+            app_handle,
+            eval_channel,
+            statuses: HashMap::new(),
+        };
+
+        loop {
+            let delay = monitor.run_round().await;
+            trace!("Next live status check in {:?}", delay);
+            tokio::time::sleep(delay).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(live: bool, title: &str) -> LiveStatus {
+        LiveStatus {
+            live,
+            title: title.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_status_change() {
+        assert_eq!(status_change(None, &status(true, "a")), None);
+        assert_eq!(
+            status_change(Some(&status(false, "a")), &status(true, "a")),
+            Some(StatusChange::WentLive)
+        );
+        assert_eq!(
+            status_change(Some(&status(true, "a")), &status(false, "a")),
+            Some(StatusChange::WentOffline)
+        );
+        assert_eq!(
+            status_change(Some(&status(true, "a")), &status(true, "b")),
+            Some(StatusChange::TitleChanged {
+                previous_title: "a".to_owned()
+            })
+        );
+        // 未开播时标题变化或接口没有返回标题都不算修改标题
+        assert_eq!(
+            status_change(Some(&status(false, "a")), &status(false, "b")),
+            None
+        );
+        assert_eq!(
+            status_change(Some(&status(true, "a")), &status(true, "")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_delay() {
+        let interval = Duration::from_secs(180);
+        let jitter = Duration::from_secs(60);
+        for _ in 0..100 {
+            let delay = next_delay(interval, jitter);
+            assert!(delay >= interval && delay <= interval + jitter);
+        }
+        assert_eq!(next_delay(interval, Duration::ZERO), interval);
+    }
+
+    #[test]
+    fn test_request_interval() {
+        let config: Config = toml::from_str(
+            r#"
+            [player]
+            path = ""
+            args = []
+
+            [platform.bilibili]
+            cookie = ""
+
+            [monitor]
+            request_interval = 1000
+            platform_request_intervals = { huya = 3000 }
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.monitor_enabled());
+        assert_eq!(config.monitor_interval(), Duration::from_secs(180));
+        assert_eq!(
+            config.request_interval(Platform::Huya),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            config.request_interval(Platform::Douyu),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn test_supported_platforms() {
+        for platform in [
+            Platform::Douyu,
+            Platform::Huya,
+            Platform::Bilibili,
+            Platform::Douyin,
+        ] {
+            assert!(supports_live_status(platform));
+        }
+        // 只能完整解析的平台不在后台查询
+        assert!(!supports_live_status(Platform::Twitch));
+        assert!(!supports_live_status(Platform::Generic));
+    }
+
+    #[test]
+    fn test_event_payload() {
+        let event = LiveStatusEvent {
+            favorite_id: 1,
+            platform: Platform::Douyu,
+            room_id: "9999".to_owned(),
+            name: "主播".to_owned(),
+            title: "新标题".to_owned(),
+            change: StatusChange::TitleChanged {
+                previous_title: "旧标题".to_owned(),
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "favoriteId": 1,
+                "platform": "douyu",
+                "roomID": "9999",
+                "name": "主播",
+                "title": "新标题",
+                "kind": "titleChanged",
+                "previousTitle": "旧标题",
+            })
+        );
+    }
+}
@@ -0,0 +1,377 @@
+mod models;
+
+use async_trait::async_trait;
+use reqwest::header::{COOKIE, REFERER};
+use url::Url;
+
+use crate::error::{InputError, LsarError, LsarResult, RoomStateError};
+use crate::parser::registry::{Capabilities, ParseContext, Parser, Site};
+use crate::parser::room::{path_segments, RoomTarget};
+use crate::parser::stream_link::{Protocol, StreamLink};
+use crate::parser::ParsedResult;
+use crate::platform::Platform;
+
+use self::models::{Representation, StartPlay, UserInfo, VideoPlayRes, VisitorLogin};
+
+use super::http_client::HttpClient;
+
+const BASE_URL: &str = "https://live.acfun.cn/live/";
+const VISITOR_LOGIN_URL: &str = "https://id.app.acfun.cn/rest/app/visitor/login";
+const START_PLAY_URL: &str = "https://api.kuaishouzt.com/rest/zt/live/web/startPlay";
+const USER_INFO_URL: &str = "https://live.acfun.cn/rest/pc-direct/user/userInfo";
+
+/// 主播未开播时 startPlay 返回的错误码
+const RESULT_NOT_LIVE: i64 = 380023;
+
+/// 游客登录得到的凭证
+#[derive(Debug)]
+struct Visitor {
+    did: String,
+    user_id: u64,
+    token: String,
+}
+
+/// 解析用到的接口地址，测试时替换为本地服务
+struct Endpoints {
+    visitor_login: String,
+    start_play: String,
+    user_info: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Endpoints {
+            visitor_login: VISITOR_LOGIN_URL.to_owned(),
+            start_play: START_PLAY_URL.to_owned(),
+            user_info: USER_INFO_URL.to_owned(),
+        }
+    }
+}
+
+pub struct AcFunParser {
+    author_id: u64,
+    endpoints: Endpoints,
+    client: HttpClient,
+}
+
+impl AcFunParser {
+    pub fn new(author_id: u64) -> Self {
+        Self::with_endpoints(author_id, Endpoints::default())
+    }
+
+    fn with_endpoints(author_id: u64, endpoints: Endpoints) -> Self {
+        AcFunParser {
+            author_id,
+            endpoints,
+            client: HttpClient::new(),
+        }
+    }
+
+    async fn visitor_login(&mut self) -> LsarResult<Visitor> {
+        let did = format!("web_{:016X}", rand::random::<u64>());
+        self.client
+            .insert_header(COOKIE, &format!("_did={}", did))?;
+        self.client
+            .insert_header(REFERER, "https://live.acfun.cn/")?;
+
+        let login: VisitorLogin = self
+            .client
+            .post(&self.endpoints.visitor_login, "sid=acfun.api.visitor")
+            .await
+            .map_err(|e| {
+                error!("Failed to login as visitor: {}", e);
+                e
+            })?;
+
+        if login.result != 0 || login.visitor_st.is_empty() {
+            error!("Visitor login failed with result: {}", login.result);
+            return Err(LsarError::Other(format!(
+                "AcFun 游客登录失败：{}",
+                login.result
+            )));
+        }
+
+        debug!("Visitor login succeeded, user ID: {}", login.user_id);
+        Ok(Visitor {
+            did,
+            user_id: login.user_id,
+            token: login.visitor_st,
+        })
+    }
+
+    async fn start_play(&self, visitor: &Visitor) -> LsarResult<StartPlay> {
+        let mut url = Url::parse(&self.endpoints.start_play)?;
+        url.query_pairs_mut()
+            .append_pair("subBiz", "mainApp")
+            .append_pair("kpn", "ACFUN_APP")
+            .append_pair("kpf", "PC_WEB")
+            .append_pair("userId", &visitor.user_id.to_string())
+            .append_pair("did", &visitor.did)
+            .append_pair("acfun.api.visitor_st", &visitor.token);
+
+        let body = format!("authorId={}&pullStreamType=FLV", self.author_id);
+        self.client.post(url.as_str(), &body).await.map_err(|e| {
+            error!("Failed to call startPlay: {}", e);
+            e
+        })
+    }
+
+    async fn get_anchor_name(&self) -> LsarResult<String> {
+        let url = format!("{}?userId={}", self.endpoints.user_info, self.author_id);
+        let info: UserInfo = self.client.get_json(&url).await.map_err(|e| {
+            error!("Failed to fetch user info: {}", e);
+            e
+        })?;
+
+        Ok(info.profile.map(|p| p.name).unwrap_or_default())
+    }
+
+    fn parse_start_play(
+        author_id: u64,
+        start_play: StartPlay,
+        anchor: String,
+    ) -> LsarResult<ParsedResult> {
+        if start_play.result == RESULT_NOT_LIVE {
+            info!("Author is not live: {}", author_id);
+            return Err(RoomStateError::Offline.into());
+        }
+
+        let data = match start_play.data {
+            Some(data) if start_play.result == 1 => data,
+            _ => {
+                error!(
+                    "startPlay failed with result {}: {}",
+                    start_play.result, start_play.error_msg
+                );
+                return Err(LsarError::Other(format!(
+                    "AcFun 接口错误：{}",
+                    start_play.error_msg
+                )));
+            }
+        };
+
+        let play_res: VideoPlayRes = serde_json::from_str(&data.video_play_res).map_err(|e| {
+            error!("Failed to parse videoPlayRes: {}", e);
+            e
+        })?;
+        let links = Self::stream_links(play_res);
+        if links.is_empty() {
+            warn!("No representation found in videoPlayRes");
+            return Err(RoomStateError::Offline.into());
+        }
+
+        Ok(ParsedResult {
+            platform: Platform::AcFun,
+            title: data.caption,
+            anchor,
+            room_id: author_id.to_string(),
+            category: String::new(),
+            links,
+            qualities: Vec::new(),
+            current_quality: None,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices: Vec::new(),
+        })
+    }
+
+    /// 所有自适应清晰度，码率从高到低
+    fn stream_links(play_res: VideoPlayRes) -> Vec<StreamLink> {
+        let mut representations: Vec<Representation> = play_res
+            .live_adaptive_manifest
+            .into_iter()
+            .flat_map(|m| m.adaptation_set.representation)
+            .collect();
+        representations.sort_by_key(|r| std::cmp::Reverse(r.bitrate));
+
+        representations
+            .into_iter()
+            .map(|r| {
+                trace!("Found {} stream ({} kbps): {}", r.name, r.bitrate, r.url);
+                let protocol = Protocol::from_url(&r.url).unwrap_or(Protocol::Flv);
+                StreamLink::new(r.url, protocol)
+                    .with_quality(r.name, r.bitrate)
+                    .with_bitrate(r.bitrate)
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Parser for AcFunParser {
+    async fn parse(&mut self) -> LsarResult<ParsedResult> {
+        info!("Starting AcFun parsing process: {}", self.author_id);
+
+        let visitor = self.visitor_login().await?;
+        let start_play = self.start_play(&visitor).await?;
+        let anchor = self.get_anchor_name().await?;
+        let result = Self::parse_start_play(self.author_id, start_play, anchor);
+
+        info!("AcFun parsing process completed");
+        result
+    }
+}
+
+pub(crate) struct AcFunSite;
+
+impl Site for AcFunSite {
+    fn platform(&self) -> Platform {
+        Platform::AcFun
+    }
+
+    fn label(&self) -> &'static str {
+        "AcFun"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        BASE_URL
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["live.acfun.cn", "m.acfun.cn"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    /// https://live.acfun.cn/live/123、https://m.acfun.cn/live/detail/123
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        let author_id = path_segments(url)
+            .last()
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or(InputError::InvalidRoomId)?;
+
+        Ok(RoomTarget::new(Platform::AcFun, author_id))
+    }
+
+    fn parser(&self, target: RoomTarget, _ctx: &ParseContext) -> Box<dyn Parser> {
+        Box::new(AcFunParser::new(target.numeric_id()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_server::{Route, TestServer};
+
+    const START_PLAY: &str = include_str!("../../../tests/fixtures/acfun/start_play.json");
+    const START_PLAY_OFFLINE: &str =
+        include_str!("../../../tests/fixtures/acfun/start_play_offline.json");
+    const VISITOR_LOGIN: &str = include_str!("../../../tests/fixtures/acfun/visitor_login.json");
+    const USER_INFO: &str = include_str!("../../../tests/fixtures/acfun/user_info.json");
+
+    async fn parse_with(start_play: &str) -> (LsarResult<ParsedResult>, TestServer) {
+        let server = TestServer::start(vec![
+            Route::post("/visitor/login", VISITOR_LOGIN),
+            Route::post("/startPlay", start_play),
+            Route::get("/userInfo", USER_INFO),
+        ])
+        .await;
+        let mut parser = AcFunParser::with_endpoints(
+            23682490,
+            Endpoints {
+                visitor_login: server.url("/visitor/login"),
+                start_play: server.url("/startPlay"),
+                user_info: server.url("/userInfo"),
+            },
+        );
+        let result = parser.parse().await;
+        (result, server)
+    }
+
+    #[tokio::test]
+    async fn test_parse_live_room() {
+        let (result, server) = parse_with(START_PLAY).await;
+        let result = result.unwrap();
+        assert_eq!(result.room_id, "23682490");
+        assert_eq!(result.anchor, "AcFun测试主播");
+        assert_eq!(result.links.len(), 4);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].body, "sid=acfun.api.visitor");
+        let did = requests[0]
+            .header("cookie")
+            .and_then(|c| c.strip_prefix("_did="))
+            .unwrap();
+
+        // startPlay 使用游客登录得到的凭证
+        let start_play = Url::parse(&server.url(&requests[1].target)).unwrap();
+        let query: Vec<_> = start_play.query_pairs().collect();
+        assert!(query.contains(&("userId".into(), "1000000123456789".into())));
+        assert!(query.contains(&("did".into(), did.into())));
+        assert!(query.contains(&(
+            "acfun.api.visitor_st".into(),
+            "ChRhY2Z1bi5hcGkudmlzaXRvci5zdBJwdGVzdC12aXNpdG9yLXRva2Vu".into()
+        )));
+        assert_eq!(requests[1].body, "authorId=23682490&pullStreamType=FLV");
+        assert_eq!(requests[2].target, "/userInfo?userId=23682490");
+    }
+
+    #[tokio::test]
+    async fn test_parse_offline_room() {
+        let (result, server) = parse_with(START_PLAY_OFFLINE).await;
+        assert!(matches!(
+            result,
+            Err(LsarError::RoomState(RoomStateError::Offline))
+        ));
+        assert_eq!(
+            server.requests()[1].target.split('?').next(),
+            Some("/startPlay")
+        );
+    }
+
+    #[test]
+    fn test_visitor_login() {
+        let login: VisitorLogin = serde_json::from_str(VISITOR_LOGIN).unwrap();
+        assert_eq!(login.result, 0);
+        assert_eq!(login.user_id, 1000000123456789);
+        assert!(!login.visitor_st.is_empty());
+    }
+
+    #[test]
+    fn test_parse_start_play() {
+        let start_play = serde_json::from_str(START_PLAY).unwrap();
+        let result =
+            AcFunParser::parse_start_play(23682490, start_play, "AcFun测试主播".to_owned())
+                .unwrap();
+        assert_eq!(result.platform, Platform::AcFun);
+        assert_eq!(result.room_id, "23682490");
+        assert_eq!(result.title, "周末一起看番");
+        assert_eq!(result.anchor, "AcFun测试主播");
+
+        let links: Vec<_> = result
+            .links
+            .iter()
+            .map(|link| serde_json::to_value(link).unwrap())
+            .collect();
+        let names: Vec<_> = links.iter().map(|l| l["quality"].clone()).collect();
+        assert_eq!(names, ["蓝光 8M", "超清", "高清", "标清"]);
+        assert_eq!(links[0]["bitrate"], 8000);
+        assert_eq!(links[0]["protocol"], "flv");
+    }
+
+    #[test]
+    fn test_parse_offline() {
+        let start_play = serde_json::from_str(START_PLAY_OFFLINE).unwrap();
+        assert!(matches!(
+            AcFunParser::parse_start_play(23682490, start_play, String::new()),
+            Err(LsarError::RoomState(RoomStateError::Offline))
+        ));
+    }
+
+    #[test]
+    fn test_room_from_input() {
+        let expected = RoomTarget::new(Platform::AcFun, 23682490);
+        assert_eq!(
+            RoomTarget::from_input("https://live.acfun.cn/live/23682490").unwrap(),
+            expected
+        );
+        assert_eq!(
+            RoomTarget::from_input("https://m.acfun.cn/live/detail/23682490").unwrap(),
+            expected
+        );
+        assert_eq!(RoomTarget::from_input("acfun:23682490").unwrap(), expected);
+    }
+}
@@ -0,0 +1,67 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct VisitorLogin {
+    /// 成功时为 0
+    pub(super) result: i64,
+    #[serde(default, rename = "userId")]
+    pub(super) user_id: u64,
+    #[serde(default, rename = "acfun.api.visitor_st")]
+    pub(super) visitor_st: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct StartPlay {
+    /// 成功时为 1
+    pub(super) result: i64,
+    #[serde(default)]
+    pub(super) error_msg: String,
+    pub(super) data: Option<StartPlayData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct StartPlayData {
+    #[serde(default)]
+    pub(super) caption: String,
+    /// 嵌套的 json 字符串，解析为 `VideoPlayRes`
+    pub(super) video_play_res: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct VideoPlayRes {
+    #[serde(default)]
+    pub(super) live_adaptive_manifest: Vec<AdaptiveManifest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct AdaptiveManifest {
+    pub(super) adaptation_set: AdaptationSet,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct AdaptationSet {
+    #[serde(default)]
+    pub(super) representation: Vec<Representation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Representation {
+    pub(super) url: String,
+    /// 码率，单位 kbps
+    #[serde(default)]
+    pub(super) bitrate: u32,
+    pub(super) name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UserInfo {
+    pub(super) profile: Option<Profile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Profile {
+    pub(super) name: String,
+}
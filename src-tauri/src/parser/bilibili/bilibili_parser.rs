@@ -1,6 +1,8 @@
+use async_trait::async_trait;
 use reqwest::Client;
 
 use crate::error::LsarResult;
+use crate::parser::registry::{ParseOptions, Parser};
 use crate::parser::ParsedResult;
 use crate::platform::Platform;
 
@@ -8,17 +10,18 @@ use super::cookie_verifier::CookieVerifier;
 use super::html_fetcher::HTMLFetcher;
 use super::link_parser::LinkParser;
 use super::room_info_fetcher::RoomInfoFetcher;
-use super::room_play_info_fetcher::RoomPlayInfoFetcher;
+use super::room_play_info_fetcher::{RoomPlayInfoFetcher, DEFAULT_QN};
 
 pub struct BilibiliParser {
     room_id: u64,
     page_url: String,
     cookie: String,
+    options: ParseOptions,
     client: Client,
 }
 
 impl BilibiliParser {
-    pub fn new(cookie: String, room_id: u64, url: Option<String>) -> Self {
+    pub fn new(cookie: String, room_id: u64, url: Option<String>, options: ParseOptions) -> Self {
         let page_url = url.unwrap_or_else(|| format!("https://live.bilibili.com/{}", room_id));
         let client = reqwest::Client::new();
 
@@ -26,26 +29,75 @@ impl BilibiliParser {
             room_id,
             page_url,
             cookie,
+            options,
             client,
         }
     }
 
-    pub async fn parse(&mut self) -> LsarResult<ParsedResult> {
-        trace!("Starting parsing process for room ID: {}", self.room_id);
+    /// 未设置 cookie 或 cookie 失效时以游客身份解析，返回是否已登录
+    async fn verify_cookie(&self, notices: &mut Vec<String>) -> bool {
+        if self.cookie.is_empty() {
+            info!("No cookie set, parsing as guest");
+            return false;
+        }
 
         let cookie_verifier = CookieVerifier::new(&self.client, &self.cookie);
         match cookie_verifier.verify().await {
-            Ok(username) => {
+            Ok(Some(username)) => {
                 info!(
                     "Cookie verification successful. Logged in user: {}",
                     username
                 );
+                true
+            }
+            Ok(None) => {
+                warn!("Cookie has expired, parsing as guest");
+                notices
+                    .push("B 站 cookie 已失效，已使用游客身份解析，请重新设置 cookie".to_owned());
+                false
             }
             Err(e) => {
-                error!("Cookie verification failed. Error: {}. Details: {:?}", e, e);
-                return Err(e);
+                warn!("Cookie verification failed, parsing as guest. Error: {}", e);
+                false
             }
-        };
+        }
+    }
+
+    fn parse_room_id(&self, html: &str) -> LsarResult<u64> {
+        trace!("Parsing room ID from HTML");
+        let room_id = html
+            .split(r#""defaultRoomId":""#)
+            .nth(1)
+            .and_then(|s| s.split('"').next())
+            .or_else(|| {
+                html.split(r#""roomid":"#)
+                    .nth(1)
+                    .and_then(|s| s.split(',').next())
+            })
+            .or_else(|| {
+                html.split(r#""roomId":"#)
+                    .nth(1)
+                    .and_then(|s| s.split(',').next())
+            })
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or_else(|| {
+                let err_msg = "Failed to parse room ID";
+                error!("{}", err_msg);
+                err_msg
+            })?;
+
+        debug!("Parsed room ID: {}", room_id);
+        Ok(room_id)
+    }
+}
+
+#[async_trait]
+impl Parser for BilibiliParser {
+    async fn parse(&mut self) -> LsarResult<ParsedResult> {
+        trace!("Starting parsing process for room ID: {}", self.room_id);
+
+        let mut notices = Vec::new();
+        let logged_in = self.verify_cookie(&mut notices).await;
 
         if self.room_id == 0 {
             let html_fetcher = HTMLFetcher::new(&self.client, &self.page_url);
@@ -82,7 +134,7 @@ impl BilibiliParser {
         };
 
         let room_play_info_fetcher =
-            RoomPlayInfoFetcher::new(&self.client, self.room_id, &self.cookie);
+            RoomPlayInfoFetcher::new(&self.client, self.room_id, &self.cookie, &self.options);
         let room_play_info = match room_play_info_fetcher.fetch().await {
             Ok(info) => {
                 debug!("Fetched room play info successfully");
@@ -100,6 +152,19 @@ impl BilibiliParser {
         let link_parser = LinkParser::new();
         let links = link_parser.parse(&room_play_info);
         debug!("Parsed {} stream links", links.len());
+        let (mut qualities, current_quality) = link_parser.qualities(&room_play_info);
+
+        if !logged_in {
+            let requested_qn = self.options.quality.unwrap_or(DEFAULT_QN);
+            let limited =
+                link_parser.mark_login_required(&mut qualities, requested_qn, current_quality);
+            if !limited.is_empty() {
+                notices.push(format!(
+                    "未登录 B 站，{} 需要设置 cookie 后才能观看",
+                    limited.join("、")
+                ));
+            }
+        }
 
         let parsed_result = ParsedResult {
             title: page_info.0,
@@ -107,7 +172,12 @@ impl BilibiliParser {
             category: page_info.2,
             platform: Platform::Bilibili,
             links,
-            room_id: self.room_id,
+            room_id: self.room_id.to_string(),
+            qualities,
+            current_quality,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices,
         };
 
         info!(
@@ -116,31 +186,4 @@ impl BilibiliParser {
         );
         Ok(parsed_result)
     }
-
-    fn parse_room_id(&self, html: &str) -> LsarResult<u64> {
-        trace!("Parsing room ID from HTML");
-        let room_id = html
-            .split(r#""defaultRoomId":""#)
-            .nth(1)
-            .and_then(|s| s.split('"').next())
-            .or_else(|| {
-                html.split(r#""roomid":"#)
-                    .nth(1)
-                    .and_then(|s| s.split(',').next())
-            })
-            .or_else(|| {
-                html.split(r#""roomId":"#)
-                    .nth(1)
-                    .and_then(|s| s.split(',').next())
-            })
-            .and_then(|s| s.parse::<u64>().ok())
-            .ok_or_else(|| {
-                let err_msg = "Failed to parse room ID";
-                error!("{}", err_msg);
-                err_msg
-            })?;
-
-        debug!("Parsed room ID: {}", room_id);
-        Ok(room_id)
-    }
 }
@@ -29,7 +29,8 @@ impl<'a> CookieVerifier<'a> {
         CookieVerifier { client, cookie }
     }
 
-    pub async fn verify(&self) -> LsarResult<String> {
+    /// 返回登录的用户名，cookie 已失效时返回 `None`
+    pub async fn verify(&self) -> LsarResult<Option<String>> {
         debug!("Starting cookie verification process");
 
         let response_value = self
@@ -50,8 +51,8 @@ impl<'a> CookieVerifier<'a> {
             error!("{}. Response code: {}", err_msg, response.code);
 
             // -101 未登录
-            if response.code == -101 && response.message == "账号未登录" {
-                return Err("账号未登录，cookie 未设置或已失效".into());
+            if response.code == -101 {
+                return Ok(None);
             }
 
             return Err(err_msg.into());
@@ -64,6 +65,6 @@ impl<'a> CookieVerifier<'a> {
         })?;
 
         debug!("Cookie verification successful for user: {}", username);
-        Ok(username)
+        Ok(Some(username))
     }
 }
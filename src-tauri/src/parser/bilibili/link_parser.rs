@@ -1,3 +1,7 @@
+use url::Url;
+
+use crate::parser::stream_link::{Codec, Protocol, QualityOption, StreamLink};
+
 use super::room_play_info_fetcher::Response;
 
 pub struct LinkParser;
@@ -7,23 +11,45 @@ impl LinkParser {
         LinkParser
     }
 
-    pub fn parse(&self, info: &Response) -> Vec<String> {
+    pub fn parse(&self, info: &Response) -> Vec<StreamLink> {
         trace!("Starting to parse stream links");
         let mut links = Vec::new();
+        let playurl = &info.data.playurl_info.playurl;
 
-        for (stream_index, stream) in info.data.playurl_info.playurl.stream.iter().enumerate() {
+        for (stream_index, stream) in playurl.stream.iter().enumerate() {
             for (format_index, format) in stream.format.iter().enumerate() {
+                let protocol = match format.format_name.as_str() {
+                    "fmp4" => Protocol::Fmp4,
+                    "ts" => Protocol::Hls,
+                    _ => Protocol::Flv,
+                };
+
                 for (codec_index, codec) in format.codec.iter().enumerate() {
                     for (url_index, url_info) in codec.url_info.iter().enumerate() {
-                        let link = format!("{}{}{}", url_info.host, codec.base_url, url_info.extra);
+                        let url = format!("{}{}{}", url_info.host, codec.base_url, url_info.extra);
                         trace!(
-                            "Parsed link: {} (Stream: {}, Format: {}, Codec: {}, URL: {})",
-                            link,
+                            "Parsed link: {} (Stream: {} {}, Format: {} {}, Codec: {} {}, URL: {})",
+                            url,
                             stream_index,
+                            stream.protocol_name,
                             format_index,
+                            format.format_name,
                             codec_index,
+                            codec.codec_name,
                             url_index
                         );
+
+                        let quality = playurl
+                            .qn_desc(codec.current_qn)
+                            .unwrap_or_else(|| qn_label(codec.current_qn));
+                        let mut link = StreamLink::new(url, protocol)
+                            .with_quality(quality, codec.current_qn)
+                            .with_codec(Codec::from_name(&codec.codec_name))
+                            .with_header("Referer", "https://live.bilibili.com/");
+                        if let Some(cdn) = cdn_name(&url_info.host) {
+                            link = link.with_cdn(cdn);
+                        }
+
                         links.push(link);
                     }
                 }
@@ -33,4 +59,119 @@ impl LinkParser {
         debug!("Parsed {} stream links", links.len());
         links
     }
+
+    /// 所有编码可选清晰度的并集，按接口返回的顺序（从高到低）
+    pub fn qualities(&self, info: &Response) -> (Vec<QualityOption>, Option<u32>) {
+        let playurl = &info.data.playurl_info.playurl;
+        let codecs = playurl
+            .stream
+            .iter()
+            .flat_map(|s| &s.format)
+            .flat_map(|f| &f.codec);
+
+        let mut qualities: Vec<QualityOption> = Vec::new();
+        let mut current_qn = None;
+        for codec in codecs {
+            current_qn.get_or_insert(codec.current_qn);
+            for &qn in &codec.accept_qn {
+                if qualities.iter().all(|q| q.value != qn) {
+                    qualities.push(QualityOption {
+                        value: qn,
+                        label: playurl
+                            .qn_desc(qn)
+                            .unwrap_or_else(|| qn_label(qn))
+                            .to_owned(),
+                        login_required: false,
+                    });
+                }
+            }
+        }
+
+        debug!(
+            "Accepted qualities: {:?}, current: {:?}",
+            qualities, current_qn
+        );
+        (qualities, current_qn)
+    }
+
+    /// 未登录时接口只返回限定清晰度以下的流，请求更高清晰度时 `current_qn` 会被降低，
+    /// 高于 `current_qn` 的清晰度即需要登录，返回这些清晰度的名称
+    pub fn mark_login_required(
+        &self,
+        qualities: &mut [QualityOption],
+        requested_qn: u32,
+        current_qn: Option<u32>,
+    ) -> Vec<String> {
+        let Some(current_qn) = current_qn.filter(|&qn| qn < requested_qn) else {
+            return Vec::new();
+        };
+
+        let limited: Vec<String> = qualities
+            .iter_mut()
+            .filter(|q| q.value > current_qn)
+            .map(|q| {
+                q.login_required = true;
+                q.label.clone()
+            })
+            .collect();
+
+        debug!(
+            "Qualities above {} require login: {:?}",
+            current_qn, limited
+        );
+        limited
+    }
+}
+
+/// 常见的 qn 对应的清晰度名称
+fn qn_label(qn: u32) -> &'static str {
+    match qn {
+        30000 => "杜比",
+        20000 => "4K",
+        10000 => "原画",
+        400 => "蓝光",
+        250 => "超清",
+        150 => "高清",
+        80 => "流畅",
+        _ => crate::parser::stream_link::DEFAULT_QUALITY,
+    }
+}
+
+/// 节点域名的第一段即 CDN 节点名，如 `cn-gddg-ct-01-01`
+fn cdn_name(host: &str) -> Option<String> {
+    Url::parse(host)
+        .ok()?
+        .host_str()?
+        .split('.')
+        .next()
+        .map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAY_INFO_GUEST: &str =
+        include_str!("../../../tests/fixtures/bilibili/play_info_guest.json");
+
+    #[test]
+    fn test_guest_qualities() {
+        let info: Response = serde_json::from_str(PLAY_INFO_GUEST).unwrap();
+        let link_parser = LinkParser::new();
+
+        let (mut qualities, current_qn) = link_parser.qualities(&info);
+        assert_eq!(current_qn, Some(150));
+        let limited = link_parser.mark_login_required(&mut qualities, 10000, current_qn);
+        assert_eq!(limited, ["原画", "蓝光", "超清"]);
+
+        let flags: Vec<_> = qualities.iter().map(|q| q.login_required).collect();
+        assert_eq!(flags, [true, true, true, false, false]);
+
+        // 请求的清晰度不高于游客上限时无法判断
+        let (mut qualities, current_qn) = link_parser.qualities(&info);
+        assert!(link_parser
+            .mark_login_required(&mut qualities, 150, current_qn)
+            .is_empty());
+        assert!(qualities.iter().all(|q| !q.login_required));
+    }
 }
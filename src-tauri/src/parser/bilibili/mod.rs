@@ -1,32 +1,78 @@
+use async_trait::async_trait;
+use url::Url;
+
 use bilibili_parser::BilibiliParser;
+use room_info_fetcher::RoomInfoFetcher;
+
+pub use qr_login::{get_bilibili_login_qrcode, poll_bilibili_login};
 
-use crate::error::LsarResult;
+use crate::error::{InputError, LsarResult};
+use crate::platform::Platform;
 
-use super::ParsedResult;
+use super::registry::{Capabilities, LiveStatus, ParseContext, Parser, Site};
+use super::room::{path_segments, RoomTarget};
 
 mod bilibili_parser;
 mod cookie_verifier;
 mod html_fetcher;
 mod link_parser;
+mod qr_login;
 mod room_info_fetcher;
 mod room_play_info_fetcher;
 
-#[tauri::command]
-pub async fn parse_bilibili(
-    room_id: u64,
-    cookie: String,
-    url: Option<String>,
-) -> LsarResult<ParsedResult> {
-    let mut parser = BilibiliParser::new(cookie, room_id, url);
-
-    match parser.parse().await {
-        Ok(result) => {
-            info!(target: "main", "Parsing successful. Result: {:?}", result);
-            Ok(result)
+pub(crate) struct BilibiliSite;
+
+#[async_trait]
+impl Site for BilibiliSite {
+    fn platform(&self) -> Platform {
+        Platform::Bilibili
+    }
+
+    fn label(&self) -> &'static str {
+        "B 站"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        "https://live.bilibili.com/"
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["live.bilibili.com"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            cookie: true,
+            live_status: true,
+            ..Default::default()
         }
-        Err(e) => {
-            error!(target: "main", "Parsing failed: {}. Error details: {:?}", e, e);
-            Err(e)
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        let segments = path_segments(url);
+
+        // 兼容 /h5/123、/blanc/123 等形式
+        match segments.last().and_then(|s| s.parse::<u64>().ok()) {
+            Some(room_id) => Ok(RoomTarget::new(Platform::Bilibili, room_id)),
+            // 活动页等需要从网页中获取房间号
+            None if !segments.is_empty() => Ok(RoomTarget::with_url(Platform::Bilibili, url)),
+            None => Err(InputError::InvalidRoomId.into()),
         }
     }
+
+    fn parser(&self, target: RoomTarget, ctx: &ParseContext) -> Box<dyn Parser> {
+        Box::new(BilibiliParser::new(
+            ctx.config.bilibili_cookie().to_owned(),
+            target.numeric_id(),
+            target.url,
+            ctx.options.clone(),
+        ))
+    }
+
+    async fn status(&self, target: RoomTarget, ctx: &ParseContext) -> LsarResult<LiveStatus> {
+        let client = reqwest::Client::new();
+        RoomInfoFetcher::new(&client, target.numeric_id(), ctx.config.bilibili_cookie())
+            .fetch_status()
+            .await
+    }
 }
@@ -0,0 +1,335 @@
+//! 扫码登录 B 站，登录成功后将 cookie 写入配置文件
+
+use std::io::Cursor;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use image::{ImageFormat, Luma};
+use qrcode::{render::svg, QrCode};
+use reqwest::header::SET_COOKIE;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{read_config_file, write_config_file};
+use crate::error::{LsarError, LsarResult};
+use crate::parser::http_client::HttpClient;
+use crate::platform::Platform;
+
+const PASSPORT_URL: &str = "https://passport.bilibili.com";
+const GENERATE_PATH: &str = "/x/passport-login/web/qrcode/generate";
+const POLL_PATH: &str = "/x/passport-login/web/qrcode/poll";
+
+/// 解析直播需要的 cookie
+const COOKIE_NAMES: [&str; 3] = ["SESSDATA", "bili_jct", "DedeUserID"];
+
+/// 二维码图片的最小边长，单位像素
+const QR_CODE_SIZE: u32 = 200;
+
+// 扫码状态
+const POLL_SUCCESS: i32 = 0;
+const POLL_EXPIRED: i32 = 86038;
+const POLL_SCANNED: i32 = 86090;
+const POLL_WAITING: i32 = 86101;
+
+#[derive(Debug, Deserialize)]
+struct PassportResponse<T> {
+    code: i32,
+    message: String,
+    data: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateData {
+    url: String,
+    qrcode_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PollData {
+    code: i32,
+    message: String,
+    /// 登录成功后的跨域跳转链接，查询参数中包含 cookie
+    #[serde(default)]
+    url: String,
+}
+
+/// 登录二维码，`key` 用于查询扫码状态
+#[derive(Debug, Serialize)]
+pub struct LoginQrCode {
+    key: String,
+    url: String,
+    svg: String,
+    /// png 图片的 data URL
+    png: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum LoginStatus {
+    Waiting,
+    /// 已扫码，等待在手机上确认
+    Scanned,
+    Expired,
+    Success {
+        #[serde(skip)]
+        cookie: String,
+    },
+}
+
+struct QrLogin {
+    base_url: String,
+    client: HttpClient,
+}
+
+impl QrLogin {
+    fn new() -> Self {
+        Self::with_base_url(PASSPORT_URL.to_owned())
+    }
+
+    fn with_base_url(base_url: String) -> Self {
+        QrLogin {
+            base_url,
+            client: HttpClient::new(),
+        }
+    }
+
+    async fn generate(&self) -> LsarResult<LoginQrCode> {
+        let url = format!("{}{}", self.base_url, GENERATE_PATH);
+        let response: PassportResponse<GenerateData> =
+            self.client.get_json(&url).await.map_err(|e| {
+                error!("Failed to generate login QR code: {}", e);
+                e
+            })?;
+
+        let data = match response.data {
+            Some(data) if response.code == 0 => data,
+            _ => {
+                error!(
+                    "Generating QR code failed with code {}: {}",
+                    response.code, response.message
+                );
+                return Err(LsarError::Other(format!(
+                    "获取登录二维码失败：{}",
+                    response.message
+                )));
+            }
+        };
+
+        let (svg, png) = render_qr_code(&data.url)?;
+        debug!("Generated login QR code: {}", data.qrcode_key);
+        Ok(LoginQrCode {
+            key: data.qrcode_key,
+            url: data.url,
+            svg,
+            png,
+        })
+    }
+
+    async fn poll(&self, key: &str) -> LsarResult<LoginStatus> {
+        let url = format!("{}{}?qrcode_key={}", self.base_url, POLL_PATH, key);
+        let response = self.client.get(&url).await?;
+
+        let set_cookies: Vec<String> = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .map(ToOwned::to_owned)
+            .collect();
+        let response: PassportResponse<PollData> = response.json().await.map_err(|e| {
+            error!("Failed to parse poll response: {}", e);
+            LsarError::Http(e.into())
+        })?;
+
+        let Some(data) = response.data.filter(|_| response.code == 0) else {
+            error!(
+                "Polling QR code failed with code {}: {}",
+                response.code, response.message
+            );
+            return Err(LsarError::Other(format!(
+                "查询扫码状态失败：{}",
+                response.message
+            )));
+        };
+
+        trace!("QR code status: {} {}", data.code, data.message);
+        match data.code {
+            POLL_WAITING => Ok(LoginStatus::Waiting),
+            POLL_SCANNED => Ok(LoginStatus::Scanned),
+            POLL_EXPIRED => Ok(LoginStatus::Expired),
+            POLL_SUCCESS => {
+                let cookie = login_cookie(&set_cookies, &data.url).ok_or_else(|| {
+                    error!("Login succeeded but cookie is incomplete");
+                    LsarError::Other("登录成功但未获取到完整的 cookie".to_owned())
+                })?;
+                info!("Bilibili QR code login succeeded");
+                Ok(LoginStatus::Success { cookie })
+            }
+            code => {
+                warn!("Unknown QR code status {}: {}", code, data.message);
+                Err(LsarError::Other(format!("扫码登录失败：{}", data.message)))
+            }
+        }
+    }
+}
+
+/// svg 文本和 png 的 data URL
+fn render_qr_code(url: &str) -> LsarResult<(String, String)> {
+    let code = QrCode::new(url.as_bytes()).map_err(|e| {
+        error!("Failed to encode QR code: {}", e);
+        LsarError::Other(format!("生成二维码失败：{}", e))
+    })?;
+
+    let svg = code
+        .render::<svg::Color>()
+        .min_dimensions(QR_CODE_SIZE, QR_CODE_SIZE)
+        .build();
+
+    let image = code
+        .render::<Luma<u8>>()
+        .min_dimensions(QR_CODE_SIZE, QR_CODE_SIZE)
+        .build();
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| {
+            error!("Failed to encode QR code as PNG: {}", e);
+            LsarError::Other(format!("生成二维码失败：{}", e))
+        })?;
+
+    Ok((
+        svg,
+        format!("data:image/png;base64,{}", BASE64_STANDARD.encode(png)),
+    ))
+}
+
+/// 优先使用响应头中的 cookie，缺少时从跳转链接的查询参数中读取，
+/// 查询参数保持原样不解码，与浏览器中的 cookie 一致
+fn login_cookie(set_cookies: &[String], url: &str) -> Option<String> {
+    let from_headers = set_cookies
+        .iter()
+        .filter_map(|c| c.split(';').next()?.trim().split_once('='));
+    let from_url = url
+        .split_once('?')
+        .map(|(_, query)| query)
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='));
+    let pairs: Vec<(&str, &str)> = from_headers.chain(from_url).collect();
+
+    COOKIE_NAMES
+        .iter()
+        .map(|name| {
+            pairs
+                .iter()
+                .find(|(k, v)| k == name && !v.is_empty())
+                .map(|(k, v)| format!("{}={}", k, v))
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|cookies| cookies.join("; "))
+}
+
+#[tauri::command]
+pub async fn get_bilibili_login_qrcode() -> LsarResult<LoginQrCode> {
+    info!("Requesting Bilibili login QR code");
+    QrLogin::new().generate().await
+}
+
+/// 查询扫码状态，登录成功时将 cookie 保存到配置文件
+#[tauri::command]
+pub async fn poll_bilibili_login(key: String) -> LsarResult<LoginStatus> {
+    let status = QrLogin::new().poll(&key).await?;
+
+    if let LoginStatus::Success { cookie } = &status {
+        let mut config = read_config_file().await?;
+        config.set_cookie(Platform::Bilibili, cookie.clone());
+        write_config_file(config).await?;
+        info!("Saved Bilibili cookie to config file");
+    }
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_server::{Route, TestServer};
+
+    const GENERATE: &str = include_str!("../../../tests/fixtures/bilibili/qrcode_generate.json");
+    const POLL_WAITING_BODY: &str =
+        include_str!("../../../tests/fixtures/bilibili/qrcode_poll_waiting.json");
+    const POLL_EXPIRED_BODY: &str =
+        include_str!("../../../tests/fixtures/bilibili/qrcode_poll_expired.json");
+    const POLL_SUCCESS_BODY: &str =
+        include_str!("../../../tests/fixtures/bilibili/qrcode_poll_success.json");
+
+    async fn poll_with(route: Route) -> LsarResult<LoginStatus> {
+        let server = TestServer::start(vec![route]).await;
+        QrLogin::with_base_url(server.url(""))
+            .poll("8f3d6a5c1b2e4f7a9c0d1e2f3a4b5c6d")
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_generate() {
+        let server = TestServer::start(vec![Route::get(GENERATE_PATH, GENERATE)]).await;
+        let qr_code = QrLogin::with_base_url(server.url(""))
+            .generate()
+            .await
+            .unwrap();
+
+        assert_eq!(qr_code.key, "8f3d6a5c1b2e4f7a9c0d1e2f3a4b5c6d");
+        assert!(qr_code.url.starts_with("https://account.bilibili.com/h5/"));
+        assert!(qr_code.svg.contains("<svg"));
+
+        let png = qr_code.png.strip_prefix("data:image/png;base64,").unwrap();
+        let png = BASE64_STANDARD.decode(png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+
+    #[tokio::test]
+    async fn test_poll_pending() {
+        assert_eq!(
+            poll_with(Route::get(POLL_PATH, POLL_WAITING_BODY))
+                .await
+                .unwrap(),
+            LoginStatus::Waiting
+        );
+        assert_eq!(
+            poll_with(Route::get(POLL_PATH, POLL_EXPIRED_BODY))
+                .await
+                .unwrap(),
+            LoginStatus::Expired
+        );
+    }
+
+    #[tokio::test]
+    async fn test_poll_success() {
+        let route = Route::get(POLL_PATH, POLL_SUCCESS_BODY)
+            .with_header(
+                "Set-Cookie",
+                "SESSDATA=5a1b2c3d%2C1775000000%2Cabcde%2A41; Path=/; Domain=bilibili.com; HttpOnly; Secure",
+            )
+            .with_header("Set-Cookie", "bili_jct=0123456789abcdef0123456789abcdef; Path=/; Domain=bilibili.com")
+            .with_header("Set-Cookie", "DedeUserID=434334701; Path=/; Domain=bilibili.com");
+
+        assert_eq!(
+            poll_with(route).await.unwrap(),
+            LoginStatus::Success {
+                cookie: "SESSDATA=5a1b2c3d%2C1775000000%2Cabcde%2A41; bili_jct=0123456789abcdef0123456789abcdef; DedeUserID=434334701".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn test_cookie_from_url() {
+        let data: PassportResponse<PollData> = serde_json::from_str(POLL_SUCCESS_BODY).unwrap();
+        assert_eq!(
+            login_cookie(&[], &data.data.unwrap().url).as_deref(),
+            Some("SESSDATA=5a1b2c3d%2C1775000000%2Cabcde%2A41; bili_jct=0123456789abcdef0123456789abcdef; DedeUserID=434334701")
+        );
+        assert!(login_cookie(
+            &[],
+            "https://passport.biligame.com/crossDomain?DedeUserID=1"
+        )
+        .is_none());
+    }
+}
@@ -2,6 +2,10 @@ use reqwest::Client;
 use serde::Deserialize;
 
 use crate::error::LsarResult;
+use crate::parser::registry::LiveStatus;
+
+/// 直播中的 `live_status`，0 为未开播，2 为轮播
+const LIVE_STATUS_LIVE: i32 = 1;
 
 #[derive(Debug, Deserialize)]
 struct RoomInfoData {
@@ -24,6 +28,26 @@ struct BaseInfo {
 struct RoomInfo {
     area_name: String,
     title: String,
+    #[serde(default)]
+    live_status: i32,
+    /// 开播时间，单位秒，未开播时为 0
+    #[serde(default)]
+    live_start_time: i64,
+    /// 人气值
+    #[serde(default)]
+    online: u64,
+}
+
+impl From<RoomInfo> for LiveStatus {
+    fn from(info: RoomInfo) -> Self {
+        let live = info.live_status == LIVE_STATUS_LIVE;
+        LiveStatus {
+            live,
+            title: info.title,
+            viewers: Some(info.online).filter(|_| live),
+            start_time: Some(info.live_start_time).filter(|t| live && *t > 0),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,7 +70,7 @@ impl<'a> RoomInfoFetcher<'a> {
         }
     }
 
-    pub async fn fetch(&self) -> LsarResult<(String, String, String)> {
+    async fn request(&self) -> LsarResult<RoomInfoData> {
         debug!("Fetching room info for room ID: {}", self.room_id);
         let url = format!(
             "https://api.live.bilibili.com/xlive/web-room/v1/index/getInfoByRoom?room_id={}",
@@ -71,7 +95,11 @@ impl<'a> RoomInfoFetcher<'a> {
                 err_msg
             })?;
 
-        let data = response.data;
+        Ok(response.data)
+    }
+
+    pub async fn fetch(&self) -> LsarResult<(String, String, String)> {
+        let data = self.request().await?;
         debug!(
             "Successfully fetched room info. Title: {}, Anchor: {}, Category: {}",
             data.room_info.title, data.anchor_info.base_info.uname, data.room_info.area_name
@@ -83,4 +111,42 @@ impl<'a> RoomInfoFetcher<'a> {
             data.room_info.area_name,
         ))
     }
+
+    pub async fn fetch_status(&self) -> LsarResult<LiveStatus> {
+        let data = self.request().await?;
+        debug!("Room live status: {}", data.room_info.live_status);
+        Ok(data.room_info.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_of(body: &str) -> LiveStatus {
+        let response: RoomInfoResponse = serde_json::from_str(body).unwrap();
+        response.data.room_info.into()
+    }
+
+    #[test]
+    fn test_live_status() {
+        assert_eq!(
+            status_of(include_str!(
+                "../../../tests/fixtures/bilibili/room_info_live.json"
+            )),
+            LiveStatus {
+                live: true,
+                title: "【歌回】周六晚间点歌".to_owned(),
+                viewers: Some(87231),
+                start_time: Some(1760767200),
+            }
+        );
+
+        // 轮播不算直播
+        let status = status_of(include_str!(
+            "../../../tests/fixtures/bilibili/room_info_round.json"
+        ));
+        assert!(!status.live);
+        assert_eq!(status.viewers, None);
+    }
 }
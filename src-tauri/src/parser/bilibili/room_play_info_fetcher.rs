@@ -3,8 +3,13 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::error::{LsarResult, RoomStateError};
+use crate::parser::registry::ParseOptions;
+use crate::parser::stream_link::{Codec, Protocol};
 
-const BASE_URL: &str = "https://api.live.bilibili.com/xlive/web-room/v2/index/getRoomPlayInfo?protocol=0,1&format=0,1,2&codec=0,1&qn=10000&platform=web&ptype=8&dolby=5&panorama=1&room_id=";
+const BASE_URL: &str = "https://api.live.bilibili.com/xlive/web-room/v2/index/getRoomPlayInfo?platform=web&ptype=8&dolby=5&panorama=1";
+
+/// 未指定清晰度时请求原画
+pub(super) const DEFAULT_QN: u32 = 10000;
 
 #[derive(Debug, Deserialize)]
 pub struct CDNItem {
@@ -14,17 +19,23 @@ pub struct CDNItem {
 
 #[derive(Debug, Deserialize)]
 pub struct CodecItem {
+    pub codec_name: String,
+    pub current_qn: u32,
+    #[serde(default)]
+    pub accept_qn: Vec<u32>,
     pub base_url: String,
     pub url_info: Vec<CDNItem>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct FormatItem {
+    pub format_name: String,
     pub codec: Vec<CodecItem>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct StreamItem {
+    pub protocol_name: String,
     pub format: Vec<FormatItem>,
 }
 
@@ -33,11 +44,29 @@ pub(super) struct PlayUrlInfo {
     pub playurl: PlayUrl,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct QnDesc {
+    pub qn: u32,
+    pub desc: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PlayUrl {
+    #[serde(default)]
+    pub g_qn_desc: Vec<QnDesc>,
     pub stream: Vec<StreamItem>,
 }
 
+impl PlayUrl {
+    /// 优先使用接口返回的清晰度名称
+    pub fn qn_desc(&self, qn: u32) -> Option<&str> {
+        self.g_qn_desc
+            .iter()
+            .find(|d| d.qn == qn)
+            .map(|d| d.desc.as_str())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub(super) struct ResponseData {
     live_status: i32,
@@ -55,20 +84,51 @@ pub struct RoomPlayInfoFetcher<'a> {
     client: &'a Client,
     room_id: u64,
     cookie: &'a str,
+    options: &'a ParseOptions,
 }
 
 impl<'a> RoomPlayInfoFetcher<'a> {
-    pub fn new(client: &'a Client, room_id: u64, cookie: &'a str) -> Self {
+    pub fn new(
+        client: &'a Client,
+        room_id: u64,
+        cookie: &'a str,
+        options: &'a ParseOptions,
+    ) -> Self {
         RoomPlayInfoFetcher {
             client,
             room_id,
             cookie,
+            options,
         }
     }
 
+    fn url(&self) -> String {
+        // protocol: 0 http_stream, 1 http_hls; format: 0 flv, 1 ts, 2 fmp4
+        let (protocol, format) = match self.options.protocol {
+            Some(Protocol::Flv) => ("0", "0"),
+            Some(Protocol::Hls) => ("1", "1"),
+            Some(Protocol::Fmp4) => ("1", "2"),
+            // 直播接口不提供 dash
+            Some(Protocol::Dash) | None => ("0,1", "0,1,2"),
+        };
+        let codec = match self.options.codec {
+            Some(Codec::Avc) => "0",
+            Some(Codec::Hevc) => "1",
+            // 直播接口不提供 av1
+            Some(Codec::Av1) | None => "0,1",
+        };
+        let qn = self.options.quality.unwrap_or(DEFAULT_QN);
+
+        format!(
+            "{}&protocol={}&format={}&codec={}&qn={}&room_id={}",
+            BASE_URL, protocol, format, codec, qn, self.room_id
+        )
+    }
+
     pub async fn fetch(&self) -> LsarResult<Response> {
         debug!("Fetching room play info for room ID: {}", self.room_id);
-        let url = format!("{}{}", BASE_URL, self.room_id);
+        let url = self.url();
+        trace!("Room play info URL: {}", url);
         let response_value = self
             .client
             .get(&url)
@@ -113,3 +173,29 @@ impl<'a> RoomPlayInfoFetcher<'a> {
         Ok(response)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_with_options() {
+        let client = Client::new();
+        let options = ParseOptions::default();
+        let fetcher = RoomPlayInfoFetcher::new(&client, 6, "", &options);
+        assert!(fetcher
+            .url()
+            .ends_with("&protocol=0,1&format=0,1,2&codec=0,1&qn=10000&room_id=6"));
+
+        let options = ParseOptions {
+            quality: Some(250),
+            protocol: Some(Protocol::Hls),
+            codec: Some(Codec::Hevc),
+            ..Default::default()
+        };
+        let fetcher = RoomPlayInfoFetcher::new(&client, 6, "", &options);
+        assert!(fetcher
+            .url()
+            .ends_with("&protocol=1&format=1&codec=1&qn=250&room_id=6"));
+    }
+}
@@ -0,0 +1,291 @@
+mod models;
+
+use async_trait::async_trait;
+use url::Url;
+
+use crate::error::{InputError, LsarResult, RoomStateError};
+use crate::parser::registry::{Capabilities, ParseContext, Parser, Site};
+use crate::parser::room::{path_segments, RoomTarget};
+use crate::parser::stream_link::{Protocol, StreamLink};
+use crate::parser::ParsedResult;
+use crate::platform::Platform;
+
+use self::models::{AnchorLives, Channel, ChannelInfo, QuickPlay};
+
+use super::http_client::HttpClient;
+
+const BASE_URL: &str = "https://cc.163.com/";
+
+/// 清晰度的键、名称和等级
+const RESOLUTIONS: &[(&str, &str, u32)] = &[
+    ("original", "原画", 5),
+    ("blueray", "蓝光", 4),
+    ("ultra", "超清", 3),
+    ("high", "高清", 2),
+    ("standard", "标清", 1),
+];
+
+/// 直播间可以用主播的 ccid 或频道 ID 表示
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Room {
+    Ccid(u64),
+    Channel(u64),
+}
+
+pub struct CCParser {
+    room: Room,
+    client: HttpClient,
+}
+
+impl CCParser {
+    fn new(room: Room) -> Self {
+        CCParser {
+            room,
+            client: HttpClient::new(),
+        }
+    }
+
+    async fn get_channel_id(&self, ccid: u64) -> LsarResult<u64> {
+        let url = format!(
+            "https://api.cc.163.com/v1/activitylives/anchor/lives?anchor_ccid={}",
+            ccid
+        );
+        let lives: AnchorLives = self.client.get_json(&url).await.map_err(|e| {
+            error!("Failed to fetch anchor lives: {}", e);
+            e
+        })?;
+
+        Self::channel_id_from_lives(ccid, lives)
+    }
+
+    fn channel_id_from_lives(ccid: u64, lives: AnchorLives) -> LsarResult<u64> {
+        let channel_id = lives
+            .data
+            .get(&ccid.to_string())
+            .map(|live| live.channel_id)
+            .ok_or_else(|| {
+                info!("No live channel found for ccid: {}", ccid);
+                RoomStateError::Offline
+            })?;
+
+        debug!("Channel ID of ccid {}: {}", ccid, channel_id);
+        Ok(channel_id)
+    }
+
+    async fn get_channel(&self, channel_id: u64) -> LsarResult<ChannelInfo> {
+        let url = format!("https://cc.163.com/live/channel/?channelids={}", channel_id);
+        self.client.get_json(&url).await.map_err(|e| {
+            error!("Failed to fetch channel info: {}", e);
+            e
+        })
+    }
+
+    fn parse_channel(info: ChannelInfo) -> LsarResult<ParsedResult> {
+        let Some(channel) = info.data.into_iter().next() else {
+            warn!("Channel info is empty");
+            return Err(RoomStateError::NotExists.into());
+        };
+
+        let links = Self::stream_links(&channel);
+        if links.is_empty() {
+            info!("No stream found for ccid: {}", channel.ccid);
+            return Err(RoomStateError::Offline.into());
+        }
+
+        Ok(ParsedResult {
+            platform: Platform::CC,
+            title: channel.title,
+            anchor: channel.nickname,
+            room_id: channel.ccid.to_string(),
+            category: channel.gamename,
+            links,
+            qualities: Vec::new(),
+            current_quality: None,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices: Vec::new(),
+        })
+    }
+
+    /// 每个清晰度按 CDN 优先级排列的 FLV 链接，最后是 HLS 链接
+    fn stream_links(channel: &Channel) -> Vec<StreamLink> {
+        let mut links = Vec::new();
+
+        if let Some(quickplay) = &channel.quickplay {
+            for (key, label, level) in RESOLUTIONS {
+                let Some(resolution) = quickplay.resolution.get(*key) else {
+                    continue;
+                };
+
+                for cdn in Self::cdn_order(quickplay, resolution.cdn.keys()) {
+                    let url = &resolution.cdn[cdn];
+                    trace!("Found {} stream on CDN {}: {}", label, cdn, url);
+                    links.push(
+                        StreamLink::new(url.clone(), Protocol::Flv)
+                            .with_quality(*label, *level)
+                            .with_bitrate(resolution.vbr)
+                            .with_cdn(cdn.as_str()),
+                    );
+                }
+            }
+        }
+
+        if !channel.sharefile.is_empty() {
+            links.push(StreamLink::new(channel.sharefile.clone(), Protocol::Hls));
+        }
+
+        links
+    }
+
+    /// 先按接口给出的优先级，其余按名称排序
+    fn cdn_order<'a>(
+        quickplay: &QuickPlay,
+        cdns: impl Iterator<Item = &'a String>,
+    ) -> Vec<&'a String> {
+        let mut cdns: Vec<_> = cdns.collect();
+        cdns.sort_by_key(|cdn| {
+            let priority = quickplay
+                .priority
+                .iter()
+                .position(|p| p == *cdn)
+                .unwrap_or(usize::MAX);
+            (priority, cdn.as_str())
+        });
+        cdns
+    }
+}
+
+#[async_trait]
+impl Parser for CCParser {
+    async fn parse(&mut self) -> LsarResult<ParsedResult> {
+        info!("Starting CC parsing process: {:?}", self.room);
+
+        let channel_id = match self.room {
+            Room::Ccid(ccid) => self.get_channel_id(ccid).await?,
+            Room::Channel(channel_id) => channel_id,
+        };
+        let info = self.get_channel(channel_id).await?;
+        let result = Self::parse_channel(info);
+
+        info!("CC parsing process completed");
+        result
+    }
+}
+
+pub(crate) struct CCSite;
+
+impl Site for CCSite {
+    fn platform(&self) -> Platform {
+        Platform::CC
+    }
+
+    fn label(&self) -> &'static str {
+        "网易 CC"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        BASE_URL
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["cc.163.com"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        if channel_id(url).is_some() {
+            return Ok(RoomTarget::with_url(Platform::CC, url));
+        }
+
+        let ccid = path_segments(url)
+            .first()
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or(InputError::InvalidRoomId)?;
+
+        Ok(RoomTarget::new(Platform::CC, ccid))
+    }
+
+    fn parser(&self, target: RoomTarget, _ctx: &ParseContext) -> Box<dyn Parser> {
+        let channel_id = target
+            .url
+            .as_deref()
+            .and_then(|url| Url::parse(url).ok())
+            .and_then(|url| channel_id(&url));
+
+        let room = match channel_id {
+            Some(channel_id) => Room::Channel(channel_id),
+            None => Room::Ccid(target.numeric_id()),
+        };
+        Box::new(CCParser::new(room))
+    }
+}
+
+/// 频道链接 https://cc.163.com/live/channel/?channelids=51348
+fn channel_id(url: &Url) -> Option<u64> {
+    url.query_pairs()
+        .find(|(k, _)| k == "channelid" || k == "channelids")
+        .and_then(|(_, v)| v.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANCHOR_LIVES: &str = include_str!("../../../tests/fixtures/cc/anchor_lives.json");
+    const ANCHOR_OFFLINE: &str = include_str!("../../../tests/fixtures/cc/anchor_offline.json");
+    const CHANNEL: &str = include_str!("../../../tests/fixtures/cc/channel.json");
+
+    #[test]
+    fn test_channel_id_from_lives() {
+        let lives = serde_json::from_str(ANCHOR_LIVES).unwrap();
+        assert_eq!(
+            CCParser::channel_id_from_lives(361433, lives).unwrap(),
+            51348
+        );
+
+        let lives = serde_json::from_str(ANCHOR_OFFLINE).unwrap();
+        assert!(matches!(
+            CCParser::channel_id_from_lives(361433, lives),
+            Err(crate::error::LsarError::RoomState(RoomStateError::Offline))
+        ));
+    }
+
+    #[test]
+    fn test_parse_channel() {
+        let result = CCParser::parse_channel(serde_json::from_str(CHANNEL).unwrap()).unwrap();
+        assert_eq!(result.platform, Platform::CC);
+        assert_eq!(result.room_id, "361433");
+        assert_eq!(result.title, "周末赛事解说");
+        assert_eq!(result.anchor, "CC测试主播");
+        assert_eq!(result.category, "永劫无间");
+
+        // 原画、超清、高清各 4 个 CDN，标清 1 个，再加 HLS
+        assert_eq!(result.links.len(), 14);
+        let first = serde_json::to_value(&result.links[0]).unwrap();
+        assert_eq!(first["quality"], "原画");
+        assert_eq!(first["cdn"], "dn");
+        assert_eq!(first["bitrate"], 6000);
+        let last = serde_json::to_value(result.links.last().unwrap()).unwrap();
+        assert_eq!(last["protocol"], "hls");
+    }
+
+    #[test]
+    fn test_room_from_input() {
+        assert_eq!(
+            RoomTarget::from_input("https://cc.163.com/361433/").unwrap(),
+            RoomTarget::new(Platform::CC, 361433)
+        );
+        assert_eq!(
+            RoomTarget::from_input("cc:361433").unwrap(),
+            RoomTarget::new(Platform::CC, 361433)
+        );
+
+        let target =
+            RoomTarget::from_input("https://cc.163.com/live/channel/?channelids=51348").unwrap();
+        assert_eq!(target.platform, Platform::CC);
+        assert!(target.url.is_some());
+    }
+}
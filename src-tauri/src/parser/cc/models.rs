@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// 按 ccid 查询正在直播的频道，未开播时 `data` 中没有对应的 ccid
+#[derive(Debug, Deserialize)]
+pub(super) struct AnchorLives {
+    #[serde(default)]
+    pub(super) data: HashMap<String, AnchorLive>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct AnchorLive {
+    pub(super) channel_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ChannelInfo {
+    #[serde(default)]
+    pub(super) data: Vec<Channel>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Channel {
+    pub(super) ccid: u64,
+    pub(super) title: String,
+    pub(super) nickname: String,
+    #[serde(default)]
+    pub(super) gamename: String,
+    /// HLS 链接
+    #[serde(default)]
+    pub(super) sharefile: String,
+    pub(super) quickplay: Option<QuickPlay>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct QuickPlay {
+    /// CDN 的优先顺序
+    #[serde(default)]
+    pub(super) priority: Vec<String>,
+    #[serde(default)]
+    pub(super) resolution: HashMap<String, Resolution>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Resolution {
+    /// 码率，单位 kbps
+    #[serde(default)]
+    pub(super) vbr: u32,
+    /// CDN 名称到 FLV 链接
+    #[serde(default)]
+    pub(super) cdn: HashMap<String, String>,
+}
@@ -1,22 +1,58 @@
 mod models;
 mod utils;
 
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
 use reqwest::header::{COOKIE, UPGRADE_INSECURE_REQUESTS};
 use serde_json::Value;
+use url::Url;
 
-use crate::error::{LsarResult, RoomStateError};
+use crate::error::{InputError, LsarError, LsarResult, MissKeyFieldError, RoomStateError};
+use crate::parser::registry::{Capabilities, LiveStatus, ParseContext, Parser, Site};
+use crate::parser::room::{path_segments, RoomTarget};
+use crate::parser::stream_link::{Codec, Protocol, StreamLink};
 use crate::parser::ParsedResult;
 use crate::platform::Platform;
 
-use self::models::{Resolution, RoomInfo};
+use self::models::{ReflowInfo, RoomInfo, UserProfileInfo};
 use self::utils::{get_ac_nonce, get_ttwid};
 
 use super::http_client::HttpClient;
-use super::Parser;
+
+const BASE_URL: &str = "https://live.douyin.com/";
+const USER_BASE_URL: &str = "https://www.douyin.com/user/";
+/// 所有 sec_uid 的共同前缀
+const SEC_UID_PREFIX: &str = "MS4wLjABAAAA";
+/// 直播中的房间状态
+const ROOM_STATUS_LIVE: u8 = 2;
+
+/// 游客 cookie 的有效时间，超过后重新获取
+const GUEST_COOKIE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// 查询直播状态时复用的游客 cookie，避免每次查询都重新获取
+static GUEST_COOKIE: Mutex<Option<GuestCookie>> = Mutex::new(None);
+
+#[derive(Debug, Clone)]
+struct GuestCookie {
+    value: String,
+    fetched_at: Instant,
+}
+
+impl GuestCookie {
+    fn is_fresh(&self) -> bool {
+        self.fetched_at.elapsed() < GUEST_COOKIE_TTL
+    }
+}
 
 pub struct DouyinParser {
     room_id: u64,
     room_url: String,
+    /// 用主页链接解析时需要先得到主播当前直播的房间号
+    sec_uid: Option<String>,
+    /// 配置中的 cookie，为空时使用游客 cookie
+    cookie: String,
     client: HttpClient,
 }
 
@@ -24,25 +60,132 @@ impl DouyinParser {
     pub fn new(room_id: u64) -> Self {
         DouyinParser {
             room_id,
-            room_url: format!("https://live.douyin.com/{}", room_id),
+            room_url: format!("{}{}", BASE_URL, room_id),
+            sec_uid: None,
+            cookie: String::new(),
             client: HttpClient::new(),
         }
     }
 
-    async fn setup_headers(&mut self) -> LsarResult<()> {
-        debug!("Setting up headers");
+    pub fn from_sec_uid(sec_uid: String) -> Self {
+        DouyinParser {
+            room_id: 0,
+            room_url: String::new(),
+            sec_uid: Some(sec_uid),
+            cookie: String::new(),
+            client: HttpClient::new(),
+        }
+    }
 
-        self.client.insert_header(UPGRADE_INSECURE_REQUESTS, "1")?;
+    pub fn with_cookie(mut self, cookie: &str) -> Self {
+        self.cookie = cookie.to_owned();
+        self
+    }
 
-        let ac_nonce = get_ac_nonce(&self.client, &self.room_url).await?;
+    /// 由 sec_uid 得到主播正在直播的房间号
+    async fn resolve_sec_uid(&mut self, sec_uid: &str) -> LsarResult<()> {
+        info!("Resolving live room of sec_uid: {}", sec_uid);
 
-        let cookie = format!("__ac_nonce={}", ac_nonce);
-        self.client.insert_header(COOKIE, &cookie)?;
-        let ttwid = get_ttwid(&self.client, &self.room_url).await?;
+        let url = format!(
+            "https://www.iesdouyin.com/web/api/v2/user/info/?sec_uid={}",
+            sec_uid
+        );
+        let profile: UserProfileInfo = self.client.get_json(&url).await.map_err(|e| {
+            error!("Failed to fetch user profile: {}", e);
+            e
+        })?;
+        let room_id = Self::live_room_id(sec_uid, profile)?;
+
+        let url = format!(
+            "https://webcast.amemv.com/webcast/room/reflow/info/?type_id=0&live_id=1&room_id={}&sec_user_id={}&version_code=99.99.99&app_id=1128",
+            room_id, sec_uid
+        );
+        let reflow: ReflowInfo = self.client.get_json(&url).await.map_err(|e| {
+            error!("Failed to fetch reflow info: {}", e);
+            e
+        })?;
+        let web_rid = Self::web_rid_from_reflow(reflow)?;
+
+        info!("sec_uid {} is live in room {}", sec_uid, web_rid);
+        self.room_id = web_rid;
+        self.room_url = format!("{}{}", BASE_URL, web_rid);
+        Ok(())
+    }
+
+    /// 用户信息中正在直播的内部房间 ID
+    fn live_room_id(sec_uid: &str, profile: UserProfileInfo) -> LsarResult<u64> {
+        let Some(user) = profile.user_info else {
+            info!("User does not exist: {}", sec_uid);
+            return Err(RoomStateError::NotExists.into());
+        };
+
+        if user.room_id == 0 {
+            info!("User {} is not live", user.nickname);
+            return Err(RoomStateError::AnchorOffline.into());
+        }
+
+        debug!("User {} is in room {}", user.nickname, user.room_id);
+        Ok(user.room_id)
+    }
 
-        let cookie = format!("__ac_nonce={}; ttwid={}", ac_nonce, ttwid);
+    fn web_rid_from_reflow(reflow: ReflowInfo) -> LsarResult<u64> {
+        let Some(room) = reflow.data.and_then(|d| d.room) else {
+            info!("Reflow info has no room");
+            return Err(RoomStateError::AnchorOffline.into());
+        };
+
+        if room.status != ROOM_STATUS_LIVE {
+            info!("Room status is {}, not live", room.status);
+            return Err(RoomStateError::AnchorOffline.into());
+        }
+
+        room.owner
+            .and_then(|owner| owner.web_rid.parse().ok())
+            .ok_or_else(|| {
+                error!("web_rid not found in reflow info");
+                MissKeyFieldError::RoomId.into()
+            })
+    }
+
+    /// 游客访问需要的 `__ac_nonce` 和 `ttwid`
+    async fn guest_cookie(&self) -> LsarResult<String> {
+        let mut client = self.client.clone();
+
+        let ac_nonce = get_ac_nonce(&client, &self.room_url).await?;
+        client.insert_header(COOKIE, &format!("__ac_nonce={}", ac_nonce))?;
+        let ttwid = get_ttwid(&client, &self.room_url).await?;
+
+        Ok(format!("__ac_nonce={}; ttwid={}", ac_nonce, ttwid))
+    }
+
+    /// 缓存的游客 cookie，过期或 `refresh` 时重新获取
+    async fn cached_guest_cookie(&self, refresh: bool) -> LsarResult<String> {
+        let cached = GUEST_COOKIE.lock().unwrap().clone();
+        if let Some(cookie) = cached.filter(|c| !refresh && c.is_fresh()) {
+            return Ok(cookie.value);
+        }
+
+        debug!("Fetching new Douyin guest cookie");
+        let value = self.guest_cookie().await?;
+        *GUEST_COOKIE.lock().unwrap() = Some(GuestCookie {
+            value: value.clone(),
+            fetched_at: Instant::now(),
+        });
+        Ok(value)
+    }
+
+    async fn setup_headers(&mut self) -> LsarResult<()> {
+        debug!("Setting up headers");
 
+        self.client.insert_header(UPGRADE_INSECURE_REQUESTS, "1")?;
+        let cookie = if self.cookie.is_empty() {
+            self.guest_cookie().await?
+        } else {
+            debug!("Using Douyin cookie from config");
+            self.cookie.clone()
+        };
         self.client.insert_header(COOKIE, &cookie)?;
+
         debug!("Headers set up successfully");
         Ok(())
     }
@@ -80,9 +223,69 @@ impl DouyinParser {
         Ok(room_info)
     }
 
+    /// 只请求房间信息接口，cookie 优先使用配置中的，未设置时使用缓存的游客 cookie
+    async fn live_status(&mut self) -> LsarResult<LiveStatus> {
+        if let Some(sec_uid) = self.sec_uid.take() {
+            match self.resolve_sec_uid(&sec_uid).await {
+                Err(LsarError::RoomState(RoomStateError::AnchorOffline)) => {
+                    return Ok(LiveStatus::default())
+                }
+                result => result?,
+            }
+        }
+
+        self.client.insert_header(UPGRADE_INSECURE_REQUESTS, "1")?;
+        if !self.cookie.is_empty() {
+            self.client.insert_header(COOKIE, &self.cookie.clone())?;
+            let info = self.get_room_info().await?;
+            return Ok(Self::room_status(info));
+        }
+
+        let cookie = self.cached_guest_cookie(false).await?;
+        self.client.insert_header(COOKIE, &cookie)?;
+        let info = match self.get_room_info().await {
+            Ok(info) if !info.data.data.is_empty() => info,
+            Err(e @ LsarError::RoomState(_)) => return Err(e),
+            // 游客 cookie 失效时接口返回空数据或无法解析的数据，重新获取后重试一次
+            result => {
+                warn!(
+                    "Room info is empty with cached guest cookie, retrying: {:?}",
+                    result.err()
+                );
+                let cookie = self.cached_guest_cookie(true).await?;
+                self.client.insert_header(COOKIE, &cookie)?;
+                self.get_room_info().await?
+            }
+        };
+        Ok(Self::room_status(info))
+    }
+
+    fn room_status(info: RoomInfo) -> LiveStatus {
+        let Some(room) = info.data.data.into_iter().next() else {
+            warn!("Room info has no stream data");
+            return LiveStatus::default();
+        };
+
+        debug!("Room status: {}", room.status);
+        let live = room.status == ROOM_STATUS_LIVE;
+        LiveStatus {
+            live,
+            title: room.title,
+            viewers: room
+                .room_view_stats
+                .map(|stats| stats.display_value)
+                .filter(|_| live),
+            start_time: None,
+        }
+    }
+
     fn parse_room_info(&self, info: RoomInfo) -> LsarResult<ParsedResult> {
         trace!("Entering parse_room_info method");
-        let room_data = &info.data.data[0];
+        // 房间被关闭或游客 cookie 失效时没有直播数据
+        let room_data = info.data.data.first().ok_or_else(|| {
+            info!("No stream data for room ID: {}", self.room_id);
+            RoomStateError::Offline
+        })?;
         let user = &info.data.user;
         let partition = &info.data.partition_road_map;
 
@@ -94,17 +297,34 @@ impl DouyinParser {
 
         trace!("Extracting stream URLs");
         let stream_url = room_data.stream_url.as_ref().unwrap();
-        let flv_url = stream_url
-            .flv_pull_url
-            .get(&Resolution::FullHd1)
-            .or_else(|| stream_url.flv_pull_url.get(&Resolution::Hd1));
-        let hls_url = stream_url
-            .hls_pull_url_map
-            .get(&Resolution::FullHd1)
-            .or_else(|| stream_url.hls_pull_url_map.get(&Resolution::Hd1));
-
-        debug!("FLV URL found: {}", flv_url.is_some());
-        debug!("HLS URL found: {}", hls_url.is_some());
+        let mut entries: Vec<_> = [
+            (&stream_url.flv_pull_url, Protocol::Flv),
+            (&stream_url.hls_pull_url_map, Protocol::Hls),
+        ]
+        .into_iter()
+        .flat_map(|(urls, protocol)| urls.iter().map(move |(r, url)| (r, url, protocol)))
+        .collect();
+        // 清晰度从高到低，同一清晰度 FLV 在前
+        entries.sort_by_key(|(r, _, protocol)| {
+            (std::cmp::Reverse(r.level()), *protocol != Protocol::Flv)
+        });
+
+        let links: Vec<StreamLink> = entries
+            .into_iter()
+            .map(|(resolution, url, protocol)| {
+                let quality = stream_url.quality(resolution);
+                let label = quality.map_or(resolution.label(), |q| q.name.as_str());
+                let mut link =
+                    StreamLink::new(url.clone(), protocol).with_quality(label, resolution.level());
+                if let Some(quality) = quality {
+                    link = link
+                        .with_codec(Codec::from_name(&quality.v_codec))
+                        .with_bitrate((quality.v_bit_rate / 1000) as u32);
+                }
+                link
+            })
+            .collect();
+        debug!("Found {} stream links", links.len());
 
         trace!("Determining stream category");
 
@@ -124,12 +344,14 @@ impl DouyinParser {
             platform: Platform::Douyin,
             anchor: user.nickname.clone(),
             title: room_data.title.clone(),
-            links: vec![
-                flv_url.cloned().unwrap_or_default(),
-                hls_url.cloned().unwrap_or_default(),
-            ],
-            room_id: self.room_id,
+            links,
+            room_id: self.room_id.to_string(),
             category,
+            qualities: Vec::new(),
+            current_quality: None,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices: Vec::new(),
         };
 
         info!("Room info parsed successfully");
@@ -137,8 +359,13 @@ impl DouyinParser {
     }
 }
 
+#[async_trait]
 impl Parser for DouyinParser {
     async fn parse(&mut self) -> LsarResult<ParsedResult> {
+        if let Some(sec_uid) = self.sec_uid.take() {
+            self.resolve_sec_uid(&sec_uid).await?;
+        }
+
         info!("Starting parsing process for room ID: {}", self.room_id);
         trace!("Attempting to set up headers");
         self.setup_headers().await?;
@@ -149,14 +376,203 @@ impl Parser for DouyinParser {
     }
 }
 
-#[tauri::command]
-pub async fn parse_douyin(room_id: u64) -> LsarResult<ParsedResult> {
-    info!("Parsing Douyin stream. Room ID: {}", room_id);
-    let mut douyin = DouyinParser::new(room_id);
-    let result = douyin.parse().await;
-    match &result {
-        Ok(_) => info!("Successfully parsed Douyin stream"),
-        Err(e) => error!("Failed to parse Douyin stream: {}", e),
+pub(crate) struct DouyinSite;
+
+#[async_trait]
+impl Site for DouyinSite {
+    fn platform(&self) -> Platform {
+        Platform::Douyin
+    }
+
+    fn label(&self) -> &'static str {
+        "抖音"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        BASE_URL
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["douyin.com", "iesdouyin.com", "webcast.amemv.com"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // 简写中可以使用主播的 sec_uid
+        Capabilities {
+            room_name: true,
+            live_status: true,
+            ..Default::default()
+        }
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        let segments = path_segments(url);
+
+        let sec_uid = match segments.as_slice() {
+            // https://www.douyin.com/user/MS4wLjABAAAA...
+            // https://www.iesdouyin.com/share/user/MS4wLjABAAAA...
+            ["user", sec_uid, ..] | ["share", "user", sec_uid, ..] => sec_uid.to_string(),
+            // 分享的直播间跳转到的页面，路径中是内部房间 ID
+            ["douyin", "webcast", "reflow", ..] => url
+                .query_pairs()
+                .find(|(k, _)| k == "sec_user_id")
+                .map(|(_, v)| v.into_owned())
+                .ok_or(InputError::InvalidRoomId)?,
+            [id, ..] if url.host_str() == Some("live.douyin.com") => {
+                if let Ok(room_id) = id.parse::<u64>() {
+                    return Ok(RoomTarget::new(Platform::Douyin, room_id));
+                }
+                id.to_string()
+            }
+            _ => return Err(InputError::InvalidRoomId.into()),
+        };
+
+        if !sec_uid.starts_with(SEC_UID_PREFIX) {
+            return Err(InputError::InvalidRoomId.into());
+        }
+        let url = Url::parse(&format!("{}{}", USER_BASE_URL, sec_uid))?;
+        Ok(RoomTarget::with_url(Platform::Douyin, &url))
+    }
+
+    fn parser(&self, target: RoomTarget, ctx: &ParseContext) -> Box<dyn Parser> {
+        Box::new(Self::douyin_parser(target, ctx))
+    }
+
+    async fn status(&self, target: RoomTarget, ctx: &ParseContext) -> LsarResult<LiveStatus> {
+        Self::douyin_parser(target, ctx).live_status().await
+    }
+}
+
+impl DouyinSite {
+    fn douyin_parser(target: RoomTarget, ctx: &ParseContext) -> DouyinParser {
+        let sec_uid = target
+            .url
+            .as_deref()
+            .and_then(|url| url.strip_prefix(USER_BASE_URL));
+
+        let parser = match sec_uid {
+            Some(sec_uid) => DouyinParser::from_sec_uid(sec_uid.to_owned()),
+            None => DouyinParser::new(target.numeric_id()),
+        };
+        parser.with_cookie(ctx.config.douyin_cookie())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::LsarError;
+
+    const SEC_UID: &str = "MS4wLjABAAAAtestSecUid-Ab_Cd0123456789";
+    const USER_LIVE: &str = include_str!("../../../tests/fixtures/douyin/user_live.json");
+    const USER_OFFLINE: &str = include_str!("../../../tests/fixtures/douyin/user_offline.json");
+    const REFLOW_LIVE: &str = include_str!("../../../tests/fixtures/douyin/reflow_live.json");
+    const REFLOW_ENDED: &str = include_str!("../../../tests/fixtures/douyin/reflow_ended.json");
+    const ENTER_LIVE: &str = include_str!("../../../tests/fixtures/douyin/enter_live.json");
+    const ENTER_OFFLINE: &str = include_str!("../../../tests/fixtures/douyin/enter_offline.json");
+    const ENTER_EMPTY: &str = include_str!("../../../tests/fixtures/douyin/enter_empty.json");
+
+    fn is_anchor_offline<T>(result: LsarResult<T>) -> bool {
+        matches!(
+            result,
+            Err(LsarError::RoomState(RoomStateError::AnchorOffline))
+        )
+    }
+
+    #[test]
+    fn test_resolve_sec_uid() {
+        let profile = serde_json::from_str(USER_LIVE).unwrap();
+        let room_id = DouyinParser::live_room_id(SEC_UID, profile).unwrap();
+        assert_eq!(room_id, 7401234567890123456);
+
+        let reflow = serde_json::from_str(REFLOW_LIVE).unwrap();
+        assert_eq!(
+            DouyinParser::web_rid_from_reflow(reflow).unwrap(),
+            646454278948
+        );
+    }
+
+    #[test]
+    fn test_resolve_sec_uid_not_live() {
+        let profile = serde_json::from_str(USER_OFFLINE).unwrap();
+        assert!(is_anchor_offline(DouyinParser::live_room_id(
+            SEC_UID, profile
+        )));
+
+        let reflow = serde_json::from_str(REFLOW_ENDED).unwrap();
+        assert!(is_anchor_offline(DouyinParser::web_rid_from_reflow(reflow)));
+
+        let profile = serde_json::from_str(r#"{"user_info":null}"#).unwrap();
+        assert!(matches!(
+            DouyinParser::live_room_id(SEC_UID, profile),
+            Err(LsarError::RoomState(RoomStateError::NotExists))
+        ));
+    }
+
+    #[test]
+    fn test_profile_inputs() {
+        let profile_url = format!("{}{}", USER_BASE_URL, SEC_UID);
+        for input in [
+            profile_url.clone(),
+            format!("https://www.douyin.com/user/{}?from_tab_name=main", SEC_UID),
+            format!("https://www.iesdouyin.com/share/user/{}?u_code=abc", SEC_UID),
+            format!(
+                "https://webcast.amemv.com/douyin/webcast/reflow/7401234567890123456?sec_user_id={}",
+                SEC_UID
+            ),
+            format!("douyin:{}", SEC_UID),
+        ] {
+            let target = RoomTarget::from_input(&input).unwrap();
+            assert_eq!(target.platform, Platform::Douyin, "{}", input);
+            assert_eq!(target.url.as_deref(), Some(profile_url.as_str()), "{}", input);
+        }
+
+        assert!(RoomTarget::from_input("https://www.douyin.com/user/self").is_err());
+        assert!(RoomTarget::from_input("https://v.douyin.com/iRNBho6u/").is_err());
+    }
+
+    #[test]
+    fn test_parse_empty_room_info() {
+        let info: RoomInfo = serde_json::from_str(ENTER_EMPTY).unwrap();
+        assert!(matches!(
+            DouyinParser::new(646454278948).parse_room_info(info),
+            Err(LsarError::RoomState(RoomStateError::Offline))
+        ));
+
+        let info: RoomInfo = serde_json::from_str(ENTER_EMPTY).unwrap();
+        assert_eq!(DouyinParser::room_status(info), LiveStatus::default());
+    }
+
+    #[test]
+    fn test_guest_cookie_expires() {
+        let mut cookie = GuestCookie {
+            value: "__ac_nonce=0; ttwid=1".to_owned(),
+            fetched_at: Instant::now(),
+        };
+        assert!(cookie.is_fresh());
+
+        cookie.fetched_at = Instant::now()
+            .checked_sub(GUEST_COOKIE_TTL + Duration::from_secs(1))
+            .unwrap();
+        assert!(!cookie.is_fresh());
+    }
+
+    #[test]
+    fn test_room_status() {
+        let info: RoomInfo = serde_json::from_str(ENTER_LIVE).unwrap();
+        assert_eq!(
+            DouyinParser::room_status(info),
+            LiveStatus {
+                live: true,
+                title: "周末户外钓鱼直播".to_owned(),
+                viewers: Some(4821),
+                start_time: None,
+            }
+        );
+
+        let info: RoomInfo = serde_json::from_str(ENTER_OFFLINE).unwrap();
+        let status = DouyinParser::room_status(info);
+        assert!(!status.live);
+        assert_eq!(status.viewers, None);
     }
-    result
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub struct RoomInfo {
@@ -19,12 +19,68 @@ pub struct StreamData {
     pub status: u8,
     pub title: String,
     pub stream_url: Option<StreamUrl>,
+    pub room_view_stats: Option<RoomViewStats>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoomViewStats {
+    /// 直播间显示的在线人数
+    #[serde(default)]
+    pub display_value: u64,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct StreamUrl {
+    #[serde(default)]
     pub flv_pull_url: HashMap<Resolution, String>,
+    #[serde(default)]
     pub hls_pull_url_map: HashMap<Resolution, String>,
+    pub live_core_sdk_data: Option<LiveCoreSdkData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LiveCoreSdkData {
+    #[serde(default)]
+    pub pull_data: Option<PullData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullData {
+    #[serde(default)]
+    pub options: PullOptions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PullOptions {
+    #[serde(default)]
+    pub qualities: Vec<Quality>,
+}
+
+/// `live_core_sdk_data` 中的清晰度信息
+#[derive(Debug, Deserialize)]
+pub struct Quality {
+    pub name: String,
+    pub sdk_key: String,
+    /// 视频码率，单位 bps
+    #[serde(default)]
+    pub v_bit_rate: u64,
+    #[serde(default)]
+    pub v_codec: String,
+}
+
+impl StreamUrl {
+    pub fn quality(&self, resolution: &Resolution) -> Option<&Quality> {
+        let qualities = &self
+            .live_core_sdk_data
+            .as_ref()?
+            .pull_data
+            .as_ref()?
+            .options
+            .qualities;
+        qualities
+            .iter()
+            .find(|q| Some(q.sdk_key.as_str()) == resolution.sdk_key())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -48,11 +104,169 @@ pub struct SubPartition {
     pub partition: Partition,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+/// 拉流地址的键，未知的键保留原值，不会导致反序列化失败
+#[derive(Debug, Deserialize, PartialEq, Eq, Hash)]
+#[serde(from = "String")]
 pub enum Resolution {
+    Origin,
     FullHd1,
     Hd1,
     Sd1,
     Sd2,
+    Ao,
+    Other(String),
+}
+
+impl From<String> for Resolution {
+    fn from(key: String) -> Self {
+        match key.as_str() {
+            "ORIGIN" => Resolution::Origin,
+            "FULL_HD1" => Resolution::FullHd1,
+            "HD1" => Resolution::Hd1,
+            "SD1" => Resolution::Sd1,
+            "SD2" => Resolution::Sd2,
+            "AO" => Resolution::Ao,
+            _ => Resolution::Other(key),
+        }
+    }
+}
+
+impl Resolution {
+    pub fn label(&self) -> &str {
+        match self {
+            Resolution::Origin => "原画",
+            Resolution::FullHd1 => "蓝光",
+            Resolution::Hd1 => "超清",
+            Resolution::Sd1 => "高清",
+            Resolution::Sd2 => "标清",
+            Resolution::Ao => "音频",
+            Resolution::Other(key) => key,
+        }
+    }
+
+    pub fn level(&self) -> u32 {
+        match self {
+            Resolution::Origin => 6,
+            Resolution::FullHd1 => 5,
+            Resolution::Hd1 => 4,
+            Resolution::Sd1 => 3,
+            Resolution::Sd2 => 2,
+            Resolution::Ao => 0,
+            Resolution::Other(_) => 1,
+        }
+    }
+
+    /// 对应 `live_core_sdk_data` 清晰度列表中的 `sdk_key`
+    fn sdk_key(&self) -> Option<&'static str> {
+        match self {
+            Resolution::Origin => Some("origin"),
+            Resolution::FullHd1 => Some("uhd"),
+            Resolution::Hd1 => Some("hd"),
+            Resolution::Sd1 => Some("sd"),
+            Resolution::Sd2 => Some("ld"),
+            Resolution::Ao => Some("ao"),
+            Resolution::Other(_) => None,
+        }
+    }
+}
+
+/// 按 sec_uid 查询的用户信息
+#[derive(Debug, Deserialize)]
+pub struct UserProfileInfo {
+    /// 用户不存在时为 null
+    pub user_info: Option<UserProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserProfile {
+    pub nickname: String,
+    /// 正在直播的房间的内部 ID，未开播时为 0
+    #[serde(default)]
+    pub room_id: u64,
+}
+
+/// 按内部房间 ID 查询的直播间信息
+#[derive(Debug, Deserialize)]
+pub struct ReflowInfo {
+    pub data: Option<ReflowData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReflowData {
+    pub room: Option<ReflowRoom>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReflowRoom {
+    /// 2 为直播中
+    #[serde(default)]
+    pub status: u8,
+    pub owner: Option<ReflowOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReflowOwner {
+    #[serde(default)]
+    pub web_rid: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_url_deserialize() {
+        let stream_url: StreamUrl = serde_json::from_str(
+            r#"{
+                "flv_pull_url": {
+                    "ORIGIN": "https://pull-flv.douyincdn.com/stage/1_or4.flv",
+                    "FULL_HD1": "https://pull-flv.douyincdn.com/stage/1.flv",
+                    "SD2": "https://pull-flv.douyincdn.com/stage/1_ld.flv",
+                    "LD_NEW": "https://pull-flv.douyincdn.com/stage/1_new.flv"
+                },
+                "hls_pull_url_map": {
+                    "FULL_HD1": "https://pull-hls.douyincdn.com/stage/1/index.m3u8"
+                },
+                "live_core_sdk_data": {
+                    "pull_data": {
+                        "options": {
+                            "qualities": [
+                                {"name": "原画", "sdk_key": "origin", "level": 6, "v_bit_rate": 6000000, "v_codec": "265"},
+                                {"name": "蓝光", "sdk_key": "uhd", "level": 5, "v_bit_rate": 4000000, "v_codec": "264"}
+                            ]
+                        },
+                        "stream_data": "{}"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(stream_url.flv_pull_url.len(), 4);
+        assert!(stream_url
+            .flv_pull_url
+            .contains_key(&Resolution::Other("LD_NEW".to_owned())));
+
+        let origin = stream_url.quality(&Resolution::Origin).unwrap();
+        assert_eq!(origin.v_bit_rate, 6000000);
+        assert_eq!(origin.v_codec, "265");
+        assert!(stream_url.quality(&Resolution::Sd2).is_none());
+    }
+
+    #[test]
+    fn test_stream_url_without_pull_data() {
+        for sdk_data in [r#"{}"#, r#"{"pull_data": null}"#] {
+            let stream_url: StreamUrl = serde_json::from_str(&format!(
+                r#"{{
+                    "flv_pull_url": {{"ORIGIN": "https://pull-flv.douyincdn.com/stage/1_or4.flv"}},
+                    "live_core_sdk_data": {}
+                }}"#,
+                sdk_data
+            ))
+            .unwrap();
+
+            assert_eq!(stream_url.flv_pull_url.len(), 1);
+            assert!(stream_url.quality(&Resolution::Origin).is_none());
+        }
+    }
 }
@@ -0,0 +1,97 @@
+use boa_engine::object::ObjectInitializer;
+use boa_engine::property::Attribute;
+use boa_engine::{js_string, Context, JsArgs, JsResult, JsString, JsValue, NativeFunction, Source};
+
+use crate::error::LsarResult;
+use crate::eval::EvalError;
+use crate::utils::md5_hex;
+
+/// 混淆后的签名函数中有大量循环，正常执行远达不到此上限，用于防止死循环
+const LOOP_ITERATION_LIMIT: u64 = 10_000_000;
+const RECURSION_LIMIT: usize = 512;
+
+/// 内置的 js 引擎，替代 webview 执行斗鱼的签名函数。
+///
+/// `Context` 不能跨线程，只在同步代码中创建和使用，不要跨越 `.await` 持有。
+pub struct JsRuntime {
+    context: Context,
+}
+
+impl JsRuntime {
+    pub fn new() -> LsarResult<Self> {
+        let mut context = Context::default();
+        context
+            .runtime_limits_mut()
+            .set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+        context
+            .runtime_limits_mut()
+            .set_recursion_limit(RECURSION_LIMIT);
+
+        // 签名函数只用到了 `CryptoJS.MD5(text).toString()`，字符串的 toString 即其本身
+        let crypto_js = ObjectInitializer::new(&mut context)
+            .function(
+                NativeFunction::from_fn_ptr(crypto_md5),
+                js_string!("MD5"),
+                1,
+            )
+            .build();
+        context
+            .register_global_property(js_string!("CryptoJS"), crypto_js, Attribute::all())
+            .map_err(script_error)?;
+
+        Ok(JsRuntime { context })
+    }
+
+    /// 执行脚本，返回最后一个表达式的值转换成的字符串
+    pub fn eval(&mut self, code: &str) -> LsarResult<String> {
+        trace!("Evaluating script natively, length: {}", code.len());
+
+        let value = self
+            .context
+            .eval(Source::from_bytes(code))
+            .map_err(script_error)?;
+        let result = value
+            .to_string(&mut self.context)
+            .map_err(script_error)?
+            .to_std_string_escaped();
+
+        trace!("Native eval result: {}", result);
+        Ok(result)
+    }
+}
+
+fn crypto_md5(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = args
+        .get_or_undefined(0)
+        .to_string(context)?
+        .to_std_string_escaped();
+
+    Ok(JsString::from(md5_hex(&text)).into())
+}
+
+fn script_error(e: boa_engine::JsError) -> EvalError {
+    error!("Native script evaluation failed: {}", e);
+    EvalError::Script(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        let mut runtime = JsRuntime::new().unwrap();
+        assert_eq!(runtime.eval("[1, 2, 3].join('-')").unwrap(), "1-2-3");
+        assert_eq!(
+            runtime
+                .eval("CryptoJS.MD5('Hello world').toString()")
+                .unwrap(),
+            "3e25960a79dbc69b674cd4ec67a72c62"
+        );
+
+        assert!(matches!(
+            runtime.eval("undefinedFunction()"),
+            Err(crate::error::LsarError::Eval(EvalError::Script(_)))
+        ));
+    }
+}
@@ -1,11 +1,19 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
 use serde_json::Value;
 use tauri::AppHandle;
+use url::Url;
 
-use crate::error::LsarResult;
-use crate::eval::{EvalChannel, EvalSender};
-use crate::parser::{ParsedResult, Parser};
+use crate::error::{InputError, LsarResult, RoomStateError};
+use crate::eval::EvalChannel;
+use crate::parser::registry::{Capabilities, LiveStatus, ParseContext, ParseOptions, Parser, Site};
+use crate::parser::room::{path_segments, RoomTarget};
+use crate::parser::ParsedResult;
+use crate::platform::Platform;
 
 mod constants;
+mod js_runtime;
 mod models;
 mod room_info_fetcher;
 mod room_page_fetcher;
@@ -20,9 +28,15 @@ use stream_info_parser::StreamInfoParser;
 
 use super::http_client::HttpClient;
 
+/// 房间信息接口，只需房间号即可请求
+const BETARD_URL: &str = "https://www.douyu.com/betard/";
+/// `show_status` 为此值时正在直播
+const SHOW_STATUS_LIVE: i64 = 1;
+
 pub struct DouyuParser {
     room_id: u64,
     final_room_id: u64,
+    options: ParseOptions,
     http_client: HttpClient,
     room_page_fetcher: RoomPageFetcher,
     signature_generator: SignatureGenerator,
@@ -31,15 +45,22 @@ pub struct DouyuParser {
 }
 
 impl DouyuParser {
-    pub fn new(room_id: u64, eval_channel_sender: EvalSender, app_handle: AppHandle) -> Self {
+    pub fn new(
+        room_id: u64,
+        options: ParseOptions,
+        eval_channel: EvalChannel,
+        app_handle: AppHandle,
+        eval_timeout: Duration,
+    ) -> Self {
         let http_client = HttpClient::new();
 
         DouyuParser {
             room_id,
             final_room_id: 0,
+            options,
             http_client: http_client.clone(),
             room_page_fetcher: RoomPageFetcher::new(http_client.clone()),
-            signature_generator: SignatureGenerator::new(eval_channel_sender, app_handle),
+            signature_generator: SignatureGenerator::new(eval_channel, app_handle, eval_timeout),
             room_info_fetcher: RoomInfoFetcher::new(http_client.clone()),
             stream_info_parser: StreamInfoParser::new(),
         }
@@ -52,14 +73,45 @@ impl DouyuParser {
     }
 
     async fn is_replay(&self) -> LsarResult<bool> {
-        let url = format!("https://www.douyu.com/betard/{}", self.final_room_id);
+        let url = format!("{}{}", BETARD_URL, self.final_room_id);
         let body: Value = self.http_client.get_json(&url).await?;
         let is_replay = body["room"]["videoLoop"].as_i64().unwrap_or(0);
         info!("Room replay status: {}", is_replay);
         Ok(is_replay == 1)
     }
+
+    /// 从 betard 接口的房间信息判断是否在直播，重播不算直播
+    fn betard_status(body: &Value) -> LsarResult<LiveStatus> {
+        let room = &body["room"];
+        if !room.is_object() {
+            error!("Room info not found in betard response");
+            return Err(RoomStateError::NotExists.into());
+        }
+
+        let show_status = room["show_status"].as_i64().unwrap_or(0);
+        let video_loop = room["videoLoop"].as_i64().unwrap_or(0);
+        debug!(
+            "Room show status: {}, video loop: {}",
+            show_status, video_loop
+        );
+        let live = show_status == SHOW_STATUS_LIVE && video_loop != 1;
+
+        // 热度可能是数字或字符串
+        let hot = &room["room_biz_all"]["hot"];
+        let viewers = hot
+            .as_u64()
+            .or_else(|| hot.as_str().and_then(|s| s.parse().ok()));
+
+        Ok(LiveStatus {
+            live,
+            title: room["room_name"].as_str().unwrap_or_default().to_owned(),
+            viewers: viewers.filter(|_| live),
+            start_time: room["show_time"].as_i64().filter(|t| live && *t > 0),
+        })
+    }
 }
 
+#[async_trait]
 impl Parser for DouyuParser {
     async fn parse(&mut self) -> LsarResult<ParsedResult> {
         trace!("Starting parsing process for Douyu stream");
@@ -72,7 +124,7 @@ impl Parser for DouyuParser {
             return Err(crate::error::RoomStateError::IsReplay.into());
         }
 
-        let signature_function = self.signature_generator.extract_signature_function(&html)?;
+        let signature_function = SignatureGenerator::extract_signature_function(&html)?;
         let params = self
             .signature_generator
             .generate_params(self.final_room_id, &signature_function)
@@ -82,7 +134,7 @@ impl Parser for DouyuParser {
 
         let room_info: RoomInfo = self
             .room_info_fetcher
-            .fetch(self.final_room_id, &params)
+            .fetch(self.final_room_id, &params, &self.options)
             .await?;
         let parsed_result = self.stream_info_parser.parse(room_info, &html).await?;
 
@@ -91,12 +143,97 @@ impl Parser for DouyuParser {
     }
 }
 
-#[tauri::command]
-pub async fn parse_douyu(
-    room_id: u64,
-    eval_channel: tauri::State<'_, EvalChannel>,
-    app_handle: tauri::AppHandle,
-) -> LsarResult<ParsedResult> {
-    let mut douyu = DouyuParser::new(room_id, eval_channel.sender.clone(), app_handle);
-    douyu.parse().await
+pub(crate) struct DouyuSite;
+
+#[async_trait]
+impl Site for DouyuSite {
+    fn platform(&self) -> Platform {
+        Platform::Douyu
+    }
+
+    fn label(&self) -> &'static str {
+        "斗鱼"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        "https://www.douyu.com/"
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["douyu.com"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            eval: true,
+            live_status: true,
+            ..Default::default()
+        }
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        let room_id = path_segments(url)
+            .first()
+            .and_then(|s| s.parse::<u64>().ok())
+            // 专题页的房间号在查询参数 rid 中
+            .or_else(|| {
+                url.query_pairs()
+                    .find(|(k, _)| k == "rid")
+                    .and_then(|(_, v)| v.parse().ok())
+            })
+            .ok_or(InputError::InvalidRoomId)?;
+
+        Ok(RoomTarget::new(Platform::Douyu, room_id))
+    }
+
+    fn parser(&self, target: RoomTarget, ctx: &ParseContext) -> Box<dyn Parser> {
+        Box::new(DouyuParser::new(
+            target.numeric_id(),
+            ctx.options.clone(),
+            ctx.eval_channel.clone(),
+            ctx.app_handle.clone(),
+            ctx.config.eval_timeout(),
+        ))
+    }
+
+    async fn status(&self, target: RoomTarget, _ctx: &ParseContext) -> LsarResult<LiveStatus> {
+        let url = format!("{}{}", BETARD_URL, target.numeric_id());
+        let body: Value = HttpClient::new().get_json(&url).await.map_err(|e| {
+            error!("Failed to fetch betard room info: {}", e);
+            e
+        })?;
+        DouyuParser::betard_status(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_betard_status() {
+        let live: Value = serde_json::from_str(include_str!(
+            "../../../tests/fixtures/douyu/betard_live.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            DouyuParser::betard_status(&live).unwrap(),
+            LiveStatus {
+                live: true,
+                title: "深夜电台，陪你聊天".to_owned(),
+                viewers: Some(356812),
+                start_time: Some(1760770800),
+            }
+        );
+
+        let replay: Value = serde_json::from_str(include_str!(
+            "../../../tests/fixtures/douyu/betard_replay.json"
+        ))
+        .unwrap();
+        let status = DouyuParser::betard_status(&replay).unwrap();
+        assert!(!status.live);
+        assert_eq!(status.start_time, None);
+
+        assert!(DouyuParser::betard_status(&serde_json::json!({ "room": null })).is_err());
+    }
 }
@@ -8,7 +8,35 @@ pub struct RoomInfo {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
 pub struct RoomData {
+    #[serde(rename = "rtmp_url")]
     pub rtmp_url: String,
+    #[serde(rename = "rtmp_live")]
     pub rtmp_live: Option<String>,
+    /// 当前链接所在的 CDN
+    #[serde(rename = "rtmp_cdn", default)]
+    pub rtmp_cdn: String,
+    /// 当前链接的码率，0 为原画
+    #[serde(default)]
+    pub rate: u32,
+    #[serde(default)]
+    pub cdns_with_name: Vec<CdnWithName>,
+    #[serde(default)]
+    pub multirates: Vec<MultiRate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CdnWithName {
+    pub name: String,
+    pub cdn: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MultiRate {
+    pub name: String,
+    pub rate: u32,
+    /// 码率，单位 kbps
+    #[serde(default)]
+    pub bit: u32,
 }
@@ -4,6 +4,8 @@ use super::constants::{INVALID_REQUEST, ROOM_OFFLINE_STATE};
 use super::models::RoomInfo;
 use crate::error::{LsarResult, RequestError, RoomStateError};
 use crate::parser::http_client::HttpClient;
+use crate::parser::registry::ParseOptions;
+use crate::parser::stream_link::Codec;
 
 pub struct RoomInfoFetcher {
     http_client: HttpClient,
@@ -38,7 +40,25 @@ impl RoomInfoFetcher {
         Ok(room_info_value)
     }
 
-    pub async fn fetch(&self, room_id: u64, params: &str) -> LsarResult<RoomInfo> {
+    /// 在签名参数后追加码率、CDN 和编码，`rate=-1` 时由服务端选择默认码率
+    fn stream_params(params: &str, options: &ParseOptions) -> String {
+        let rate = options
+            .quality
+            .map(|rate| rate.to_string())
+            .unwrap_or_else(|| "-1".to_owned());
+        let cdn = options.cdn.as_deref().unwrap_or_default();
+        let hevc = u8::from(options.codec == Some(Codec::Hevc));
+
+        format!("{}&cdn={}&rate={}&hevc={}", params, cdn, rate, hevc)
+    }
+
+    pub async fn fetch(
+        &self,
+        room_id: u64,
+        params: &str,
+        options: &ParseOptions,
+    ) -> LsarResult<RoomInfo> {
+        let params = &Self::stream_params(params, options);
         let mut room_info_value = self.request(room_id, params, false).await?;
         debug!("Received response for room info 1/2: {}", room_info_value);
 
@@ -83,3 +103,27 @@ impl RoomInfoFetcher {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_params() {
+        assert_eq!(
+            RoomInfoFetcher::stream_params("v=1&sign=x", &ParseOptions::default()),
+            "v=1&sign=x&cdn=&rate=-1&hevc=0"
+        );
+
+        let options = ParseOptions {
+            quality: Some(4),
+            cdn: Some("hw-h5".to_owned()),
+            codec: Some(Codec::Hevc),
+            ..Default::default()
+        };
+        assert_eq!(
+            RoomInfoFetcher::stream_params("v=1&sign=x", &options),
+            "v=1&sign=x&cdn=hw-h5&rate=4&hevc=1"
+        );
+    }
+}
@@ -1,29 +1,31 @@
+use std::time::Duration;
+
 use regex::Regex;
 use tauri::AppHandle;
-use tauri::Emitter;
 
 use super::constants::DEVICE_ID;
+use super::js_runtime::JsRuntime;
 use crate::error::LsarResult;
-use crate::eval::EvalError;
-use crate::eval::EvalSender;
-use crate::eval::EVAL_EVENT;
+use crate::eval::EvalChannel;
 use crate::parser::time::now;
 use crate::utils::md5;
 
 pub struct SignatureGenerator {
-    eval_channel_sender: EvalSender,
+    eval_channel: EvalChannel,
     app_handle: AppHandle,
+    eval_timeout: Duration,
 }
 
 impl SignatureGenerator {
-    pub fn new(eval_channel_sender: EvalSender, app_handle: AppHandle) -> Self {
+    pub fn new(eval_channel: EvalChannel, app_handle: AppHandle, eval_timeout: Duration) -> Self {
         SignatureGenerator {
-            eval_channel_sender,
+            eval_channel,
             app_handle,
+            eval_timeout,
         }
     }
 
-    pub fn extract_signature_function(&self, html: &str) -> LsarResult<String> {
+    pub fn extract_signature_function(html: &str) -> LsarResult<String> {
         trace!("Extracting signature function from HTML");
 
         let re = Regex::new(
@@ -45,6 +47,7 @@ impl SignatureGenerator {
         Ok(ub98484234)
     }
 
+    /// 优先使用内置 js 引擎生成签名，失败时再交给 webview 执行
     pub async fn generate_params(
         &self,
         room_id: u64,
@@ -52,6 +55,62 @@ impl SignatureGenerator {
     ) -> LsarResult<String> {
         trace!("Generating signature params");
 
+        let timestamp = now()?.as_secs();
+        // js 引擎是同步执行的，放到阻塞线程中以免占用异步运行时
+        let function = signature_function.to_owned();
+        let native = tokio::task::spawn_blocking(move || {
+            Self::generate_params_natively(room_id, &function, timestamp)
+        })
+        .await;
+        match native {
+            Ok(Ok(params)) => return Ok(params),
+            Ok(Err(e)) => warn!(
+                "Failed to generate signature natively, falling back to webview: {}",
+                e
+            ),
+            Err(e) => warn!(
+                "Native signature task failed, falling back to webview: {}",
+                e
+            ),
+        }
+
+        self.generate_params_in_webview(room_id, signature_function)
+            .await
+    }
+
+    pub fn generate_params_natively(
+        room_id: u64,
+        signature_function: &str,
+        timestamp: u64,
+    ) -> LsarResult<String> {
+        trace!("Generating signature params natively");
+        let mut runtime = JsRuntime::new()?;
+
+        let strc = runtime.eval(&format!(
+            "{}ub98484234({}, {}, {})",
+            signature_function, room_id, DEVICE_ID, timestamp
+        ))?;
+        let sign_function = &strc[..strc.rfind(')').ok_or_else(|| {
+            error!("Unexpected signature function: {}", strc);
+            crate::error::MissKeyFieldError::SignatureFunction
+        })? + 1];
+
+        let signature_params = runtime.eval(&format!(
+            "{}({}, \"{}\", {})",
+            sign_function, room_id, DEVICE_ID, timestamp
+        ))?;
+
+        debug!("Signature params generated natively");
+        Ok(signature_params)
+    }
+
+    async fn generate_params_in_webview(
+        &self,
+        room_id: u64,
+        signature_function: &str,
+    ) -> LsarResult<String> {
+        trace!("Generating signature params in webview");
+
         let timestamp = now()?.as_secs();
 
         let x = format!(
@@ -97,23 +156,43 @@ impl SignatureGenerator {
     async fn wait_eval_result(&self, x: &str) -> LsarResult<String> {
         trace!("Waiting for eval result");
 
-        let (tx, rx) = tokio::sync::oneshot::channel();
-
-        {
-            let mut sender = self.eval_channel_sender.lock().await;
-            *sender = Some(tx);
-        }
-
-        self.app_handle
-            .emit(EVAL_EVENT, x)
-            .expect("failed to emit event");
-
-        let result = rx.await.map_err(|e| {
-            error!("Failed to receive eval result: {}", e);
-            EvalError::ChannelReceiveError
-        })?;
+        let result = self
+            .eval_channel
+            .eval(&self.app_handle, x.to_owned(), self.eval_timeout)
+            .await?;
 
         trace!("Eval result received successfully");
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::md5_hex;
+
+    const ROOM_PAGE: &str = include_str!("../../../tests/fixtures/douyu/room_page.html");
+
+    #[test]
+    fn test_generate_params_natively() {
+        let signature_function = SignatureGenerator::extract_signature_function(ROOM_PAGE).unwrap();
+        assert!(signature_function.contains("return strc;}"));
+
+        let params =
+            SignatureGenerator::generate_params_natively(9999, &signature_function, 1729224000)
+                .unwrap();
+        let sign = md5_hex(&format!("9999{}1729224000220120241018", DEVICE_ID));
+        assert_eq!(
+            params,
+            format!(
+                "v=220120241018&did={}&tt=1729224000&sign={}",
+                DEVICE_ID, sign
+            )
+        );
+    }
+
+    #[test]
+    fn test_extract_signature_function_missing() {
+        assert!(SignatureGenerator::extract_signature_function("<html></html>").is_err());
+    }
+}
@@ -2,6 +2,7 @@ use regex::Regex;
 
 use super::models::RoomInfo;
 use crate::error::{LsarResult, MissKeyFieldError, RoomStateError};
+use crate::parser::stream_link::{CdnOption, Protocol, QualityOption, StreamLink};
 use crate::parser::ParsedResult;
 use crate::platform::Platform;
 
@@ -42,19 +43,56 @@ impl StreamInfoParser {
             return Err(RoomStateError::Offline.into());
         }
 
-        let stream_url = format!(
-            "{}/{}",
-            room_info.data.rtmp_url,
-            room_info.data.rtmp_live.unwrap()
+        let data = room_info.data;
+        let stream_url = format!("{}/{}", data.rtmp_url, data.rtmp_live.unwrap());
+
+        let mut link = StreamLink::new(
+            stream_url.clone(),
+            Protocol::from_url(&stream_url).unwrap_or(Protocol::Flv),
+        );
+        if let Some(rate) = data.multirates.iter().find(|r| r.rate == data.rate) {
+            link = link
+                .with_quality(rate.name.as_str(), rate.bit)
+                .with_bitrate(rate.bit);
+        }
+        if !data.rtmp_cdn.is_empty() {
+            link = link.with_cdn(data.rtmp_cdn.as_str());
+        }
+        debug!(
+            "Stream link on CDN {} with rate {}: {}",
+            data.rtmp_cdn, data.rate, stream_url
         );
 
+        let qualities = data
+            .multirates
+            .iter()
+            .map(|r| QualityOption {
+                value: r.rate,
+                label: r.name.clone(),
+                login_required: false,
+            })
+            .collect();
+        let cdns = data
+            .cdns_with_name
+            .iter()
+            .map(|c| CdnOption {
+                value: c.cdn.clone(),
+                label: c.name.clone(),
+            })
+            .collect();
+
         let parsed_result = ParsedResult {
             platform: Platform::Douyu,
             title: self.parse_stream_title(html)?,
             anchor: self.parse_anchor_name(html)?,
-            room_id: self.extract_final_room_id(html)?,
+            room_id: self.extract_final_room_id(html)?.to_string(),
             category: self.parse_stream_category(html),
-            links: vec![stream_url],
+            links: vec![link],
+            qualities,
+            current_quality: Some(data.rate),
+            cdns,
+            current_cdn: Some(data.rtmp_cdn).filter(|cdn| !cdn.is_empty()),
+            notices: Vec::new(),
         };
 
         info!("Stream info parsed successfully");
@@ -101,3 +139,22 @@ impl StreamInfoParser {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOM_PAGE: &str = include_str!("../../../tests/fixtures/douyu/room_page.html");
+
+    #[test]
+    fn test_parse_room_page() {
+        let parser = StreamInfoParser::new();
+        assert_eq!(parser.extract_final_room_id(ROOM_PAGE).unwrap(), 9999);
+        assert_eq!(parser.parse_anchor_name(ROOM_PAGE).unwrap(), "测试主播");
+        assert_eq!(
+            parser.parse_stream_title(ROOM_PAGE).unwrap(),
+            "周末夜间直播"
+        );
+        assert_eq!(parser.parse_stream_category(ROOM_PAGE), "颜值");
+    }
+}
@@ -0,0 +1,376 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use regex::Regex;
+use time::OffsetDateTime;
+use url::Url;
+
+use crate::config::Config;
+use crate::db::upsert_history;
+use crate::error::{LsarError, LsarResult};
+use crate::history::HistoryItem;
+use crate::parser::registry::{Capabilities, ParseContext, Parser, Site};
+use crate::parser::room::RoomTarget;
+use crate::parser::stream_link::{Protocol, StreamLink};
+use crate::parser::ParsedResult;
+use crate::platform::Platform;
+
+use super::http_client::HttpClient;
+
+/// 网页中的直播流链接，协议可省略
+static MEDIA_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(?:https?:)?//[^\s"'<>()\\]+?\.(?:m3u8|flv|mpd)(?:\?[^\s"'<>()\\]*)?"#)
+        .unwrap()
+});
+static TITLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+
+/// 最多验证的候选链接数量
+const MAX_CANDIDATES: usize = 10;
+
+/// 单次请求的超时时间，任意网站都可能没有响应
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 最多读取的网页大小，链接可能在内联脚本中
+pub(crate) const MAX_PAGE_BYTES: usize = 2 * 1024 * 1024;
+
+/// 识别分享链接时已下载的网页
+#[derive(Debug)]
+pub(crate) struct FetchedPage {
+    /// 跟随重定向后的链接
+    pub(crate) url: Url,
+    pub(crate) html: String,
+}
+
+/// 直接指向 m3u8、flv、mpd 文件的链接
+pub(crate) fn is_media_url(url: &str) -> bool {
+    matches!(
+        Protocol::from_url(url),
+        Some(Protocol::Flv | Protocol::Hls | Protocol::Dash)
+    )
+}
+
+/// 直接播放媒体链接并记入历史，不再请求网页
+pub(crate) async fn play_media_url(config: &Config, url: &str) -> LsarResult<ParsedResult> {
+    info!("Playing media URL directly: {}", url);
+    config.play(url.to_owned())?;
+
+    let result = GenericParser::media_result(url)?;
+    let history = HistoryItem::new(
+        0,
+        Platform::Generic,
+        result.room_id.clone(),
+        result.anchor.clone(),
+        String::new(),
+        result.title.clone(),
+        OffsetDateTime::now_utc(),
+    );
+    upsert_history(&history).await?;
+
+    Ok(result)
+}
+
+pub struct GenericParser {
+    page_url: String,
+    page: Option<FetchedPage>,
+    client: HttpClient,
+}
+
+impl GenericParser {
+    pub fn new(page_url: String) -> Self {
+        GenericParser {
+            page_url,
+            page: None,
+            client: HttpClient::with_timeout(REQUEST_TIMEOUT),
+        }
+    }
+
+    /// 使用已下载的网页，不再请求 `page_url`
+    pub(crate) fn from_page(page_url: String, page: FetchedPage) -> Self {
+        GenericParser {
+            page: Some(page),
+            ..Self::new(page_url)
+        }
+    }
+
+    /// 媒体链接本身作为唯一的链接，标题为文件名
+    fn media_result(url: &str) -> LsarResult<ParsedResult> {
+        let parsed = Url::parse(url)?;
+        let protocol = Protocol::from_url(url).unwrap_or(Protocol::Hls);
+        let title = parsed
+            .path_segments()
+            .and_then(|mut s| s.next_back())
+            .unwrap_or_default()
+            .to_owned();
+
+        Ok(ParsedResult {
+            platform: Platform::Generic,
+            title,
+            anchor: parsed.host_str().unwrap_or_default().to_owned(),
+            room_id: url.to_owned(),
+            category: String::new(),
+            links: vec![StreamLink::new(url.to_owned(), protocol)],
+            qualities: Vec::new(),
+            current_quality: None,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices: Vec::new(),
+        })
+    }
+
+    /// 网页和内联脚本中的媒体链接，去掉 json 转义并补全协议，按出现顺序去重
+    fn find_candidates(html: &str, page_url: &Url) -> Vec<String> {
+        let text = html
+            .replace("\\/", "/")
+            .replace("\\u002F", "/")
+            .replace("\\u002f", "/")
+            .replace("&amp;", "&")
+            .replace("\\u0026", "&");
+
+        let mut candidates: Vec<String> = Vec::new();
+        for m in MEDIA_URL_RE.find_iter(&text) {
+            let Ok(url) = page_url.join(m.as_str()) else {
+                continue;
+            };
+            let url = url.to_string();
+            if !candidates.contains(&url) {
+                trace!("Found candidate: {}", url);
+                candidates.push(url);
+            }
+        }
+
+        debug!("Found {} candidate media URLs", candidates.len());
+        candidates
+    }
+
+    fn find_title(html: &str) -> Option<String> {
+        TITLE_RE
+            .captures(html)
+            .map(|c| c[1].trim().to_owned())
+            .filter(|title| !title.is_empty())
+    }
+
+    /// 先用 HEAD 请求，服务器不支持时再用 GET 请求，GET 只等待响应头，不读取直播流
+    async fn probe(client: &HttpClient, url: &str) -> bool {
+        if let Ok(response) = client.head(url).await {
+            if response.status().is_success() {
+                return true;
+            }
+            trace!("HEAD {} returned {}", url, response.status());
+        }
+
+        match client.get(url).await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        }
+    }
+
+    /// 同时验证所有候选链接，保留可访问的链接并保持原有顺序
+    async fn probe_all(&self, candidates: Vec<String>) -> Vec<StreamLink> {
+        let tasks: Vec<_> = candidates
+            .into_iter()
+            .map(|url| {
+                let client = self.client.clone();
+                let probe_url = url.clone();
+                let task = tokio::spawn(async move { Self::probe(&client, &probe_url).await });
+                (url, task)
+            })
+            .collect();
+
+        let mut links = Vec::new();
+        for (url, task) in tasks {
+            if !task.await.unwrap_or(false) {
+                debug!("Candidate is not reachable: {}", url);
+                continue;
+            }
+            let protocol = Protocol::from_url(&url).unwrap_or(Protocol::Hls);
+            links.push(StreamLink::new(url, protocol));
+        }
+        links
+    }
+}
+
+#[async_trait]
+impl Parser for GenericParser {
+    async fn parse(&mut self) -> LsarResult<ParsedResult> {
+        info!("Starting generic parsing process: {}", self.page_url);
+
+        let (page_url, html) = match self.page.take() {
+            Some(page) => {
+                debug!("Using fetched page: {}", page.url);
+                (page.url, page.html)
+            }
+            None => {
+                let response = self.client.get(&self.page_url).await.map_err(|e| {
+                    error!("Failed to fetch page: {}", e);
+                    e
+                })?;
+                let html = HttpClient::read_text_limited(response, MAX_PAGE_BYTES).await?;
+                (Url::parse(&self.page_url)?, html)
+            }
+        };
+
+        let mut candidates = Self::find_candidates(&html, &page_url);
+        candidates.truncate(MAX_CANDIDATES);
+        let links = self.probe_all(candidates).await;
+
+        if links.is_empty() {
+            info!("No stream found in page: {}", self.page_url);
+            return Err(LsarError::Other("未在网页中找到直播流".to_owned()));
+        }
+
+        let host = page_url.host_str().unwrap_or_default().to_owned();
+        let result = ParsedResult {
+            platform: Platform::Generic,
+            title: Self::find_title(&html).unwrap_or_else(|| host.clone()),
+            anchor: host,
+            room_id: self.page_url.clone(),
+            category: String::new(),
+            links,
+            qualities: Vec::new(),
+            current_quality: None,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices: Vec::new(),
+        };
+
+        info!("Generic parsing process completed");
+        Ok(result)
+    }
+}
+
+/// 没有专用解析器的网站，不按域名匹配，由 `RoomTarget` 在无法识别平台时使用
+pub(crate) struct GenericSite;
+
+impl Site for GenericSite {
+    fn platform(&self) -> Platform {
+        Platform::Generic
+    }
+
+    fn label(&self) -> &'static str {
+        "其他网站"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        ""
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        let mut target = RoomTarget::with_url(Platform::Generic, url);
+        target.room_id = url.to_string();
+        Ok(target)
+    }
+
+    fn parser(&self, target: RoomTarget, _ctx: &ParseContext) -> Box<dyn Parser> {
+        Box::new(GenericParser::new(target.room_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_server::{Route, TestServer};
+
+    const PAGE: &str = include_str!("../../tests/fixtures/generic/page.html");
+
+    #[test]
+    fn test_find_candidates() {
+        let page_url = Url::parse("https://live.example.org/room/1").unwrap();
+        assert_eq!(
+            GenericParser::find_candidates(PAGE, &page_url),
+            [
+                "https://cdn.example.org/live/stream.m3u8?token=abc&expires=1760000000",
+                "https://cdn.example.org/live/stream.flv",
+                "https://cdn.example.org/live/stream.mpd",
+                "https://cdn.example.org/live/escaped.m3u8?a=1&b=2",
+                "https://cdn.example.org/live/dead.flv",
+            ]
+        );
+        assert_eq!(
+            GenericParser::find_title(PAGE).as_deref(),
+            Some("示例直播间")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parse_page() {
+        let server = TestServer::start_with(|addr| {
+            // 候选链接指向本地服务
+            let page = PAGE
+                .replace("https:", "http:")
+                .replace("cdn.example.org", addr);
+            vec![
+                Route::get("/room/1", page),
+                Route::head("/live/stream.m3u8"),
+                // 不支持 HEAD 的服务器
+                Route::head("/live/stream.flv").with_status(405),
+                Route::get("/live/stream.flv", "FLV"),
+                Route::head("/live/stream.mpd"),
+                Route::head("/live/escaped.m3u8"),
+            ]
+        })
+        .await;
+
+        let mut parser = GenericParser::new(server.url("/room/1"));
+        let result = parser.parse().await.unwrap();
+        assert_eq!(result.platform, Platform::Generic);
+        assert_eq!(result.title, "示例直播间");
+        assert_eq!(result.room_id, server.url("/room/1"));
+
+        let links: Vec<_> = result
+            .links
+            .iter()
+            .map(|link| serde_json::to_value(link).unwrap())
+            .collect();
+        let protocols: Vec<_> = links.iter().map(|l| l["protocol"].clone()).collect();
+        assert_eq!(protocols, ["hls", "flv", "dash", "hls"]);
+        assert!(links[3]["url"]
+            .as_str()
+            .unwrap()
+            .ends_with("/live/escaped.m3u8?a=1&b=2"));
+    }
+
+    #[tokio::test]
+    async fn test_parse_fetched_page() {
+        let server = TestServer::start(vec![Route::head("/live/stream.m3u8")]).await;
+        let page = FetchedPage {
+            url: Url::parse(&server.url("/room/1")).unwrap(),
+            html: format!(
+                r#"<title>已下载</title><video src="{}"></video>"#,
+                server.url("/live/stream.m3u8")
+            ),
+        };
+
+        let mut parser = GenericParser::from_page("https://short.example.org/abc".to_owned(), page);
+        let result = parser.parse().await.unwrap();
+        assert_eq!(result.title, "已下载");
+        assert_eq!(result.room_id, "https://short.example.org/abc");
+        assert_eq!(result.links.len(), 1);
+
+        // 只验证了候选链接，没有再次请求网页
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "HEAD");
+    }
+
+    #[test]
+    fn test_media_url() {
+        assert!(is_media_url("https://cdn.example.org/live/a.m3u8?t=1"));
+        assert!(is_media_url("https://cdn.example.org/live/a.flv"));
+        assert!(!is_media_url("https://cdn.example.org/live/index.html"));
+
+        let result = GenericParser::media_result("https://cdn.example.org/live/a.flv").unwrap();
+        assert_eq!(result.title, "a.flv");
+        assert_eq!(result.anchor, "cdn.example.org");
+        assert_eq!(result.links.len(), 1);
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use super::stream_link::{Codec, Protocol, StreamLink};
+
+/// HLS 主播放列表中的一个码流
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Variant {
+    pub(crate) url: String,
+    /// 峰值码率，单位 bps
+    pub(crate) bandwidth: u64,
+    /// 宽和高
+    pub(crate) resolution: Option<(u32, u32)>,
+    pub(crate) codecs: Option<String>,
+    pub(crate) frame_rate: Option<f64>,
+    /// `#EXT-X-MEDIA` 中同组视频的名称，如 `1080p60 (source)`
+    pub(crate) name: Option<String>,
+}
+
+impl Variant {
+    /// 清晰度名称，没有名称时由分辨率和帧率生成，如 `720p60`
+    pub(crate) fn quality(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+
+        match self.resolution {
+            Some((_, height)) => match self.frame_rate.map(|f| f.round() as u32) {
+                Some(fps) if fps > 30 => format!("{}p{}", height, fps),
+                _ => format!("{}p", height),
+            },
+            None if self.is_audio_only() => "audio_only".to_owned(),
+            None => format!("{}k", self.bandwidth / 1000),
+        }
+    }
+
+    pub(crate) fn codec(&self) -> Option<Codec> {
+        let codecs = self.codecs.as_deref()?;
+        codecs.split(',').map(str::trim).find_map(|c| {
+            match c.split('.').next().unwrap_or_default() {
+                "avc1" | "avc3" => Some(Codec::Avc),
+                "hvc1" | "hev1" => Some(Codec::Hevc),
+                "av01" => Some(Codec::Av1),
+                _ => None,
+            }
+        })
+    }
+
+    fn is_audio_only(&self) -> bool {
+        self.codecs
+            .as_deref()
+            .is_some_and(|c| c.split(',').all(|c| c.trim().starts_with("mp4a")))
+    }
+
+    /// 转为 HLS 链接，等级为以 kbps 为单位的码率
+    pub(crate) fn into_stream_link(self) -> StreamLink {
+        let quality = self.quality();
+        let codec = self.codec();
+        let kbps = (self.bandwidth / 1000) as u32;
+
+        StreamLink::new(self.url, Protocol::Hls)
+            .with_quality(quality, kbps)
+            .with_codec(codec)
+            .with_bitrate(kbps)
+    }
+}
+
+/// 解析主播放列表，相对链接以 `base_url` 补全，码流按码率从高到低排列
+pub(crate) fn parse_master_playlist(text: &str, base_url: &Url) -> Vec<Variant> {
+    trace!("Parsing HLS master playlist");
+
+    // GROUP-ID 到 NAME
+    let mut media_names = HashMap::new();
+    let mut variants = Vec::new();
+    let mut pending: Option<HashMap<String, String>> = None;
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(attrs) = line.strip_prefix("#EXT-X-MEDIA:") {
+            let attrs = parse_attributes(attrs);
+            if attrs.get("TYPE").map(String::as_str) == Some("VIDEO") {
+                if let (Some(group), Some(name)) = (attrs.get("GROUP-ID"), attrs.get("NAME")) {
+                    media_names.insert(group.clone(), name.clone());
+                }
+            }
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            pending = Some(parse_attributes(attrs));
+        } else if line.starts_with('#') {
+            continue;
+        } else if let Some(attrs) = pending.take() {
+            let Ok(url) = base_url.join(line) else {
+                warn!("Invalid variant URL in playlist: {}", line);
+                continue;
+            };
+
+            let variant = Variant {
+                url: url.to_string(),
+                bandwidth: attrs
+                    .get("BANDWIDTH")
+                    .and_then(|b| b.parse().ok())
+                    .unwrap_or_default(),
+                resolution: attrs.get("RESOLUTION").and_then(|r| {
+                    let (w, h) = r.split_once('x')?;
+                    Some((w.parse().ok()?, h.parse().ok()?))
+                }),
+                codecs: attrs.get("CODECS").cloned(),
+                frame_rate: attrs.get("FRAME-RATE").and_then(|f| f.parse().ok()),
+                name: attrs
+                    .get("VIDEO")
+                    .and_then(|group| media_names.get(group))
+                    .cloned(),
+            };
+            trace!("Found variant: {:?}", variant);
+            variants.push(variant);
+        }
+    }
+
+    variants.sort_by_key(|v| std::cmp::Reverse(v.bandwidth));
+    debug!("Parsed {} variants from master playlist", variants.len());
+    variants
+}
+
+/// 解析 `KEY=VALUE,KEY="VALUE"` 形式的属性列表，引号中的逗号不分隔属性
+fn parse_attributes(s: &str) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let Some((key, value)) = rest.split_once('=') else {
+            break;
+        };
+
+        let (value, next) = match value.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, next)) => (value, next.trim_start_matches(',')),
+                None => (quoted, ""),
+            },
+            None => value.split_once(',').unwrap_or((value, "")),
+        };
+
+        attrs.insert(key.trim().to_owned(), value.to_owned());
+        rest = next;
+    }
+
+    attrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASTER: &str = r#"#EXTM3U
+#EXT-X-MEDIA:TYPE=VIDEO,GROUP-ID="chunked",NAME="1080p60 (source)",AUTOSELECT=YES,DEFAULT=YES
+#EXT-X-STREAM-INF:BANDWIDTH=6000000,RESOLUTION=1920x1080,CODECS="avc1.64002A,mp4a.40.2",VIDEO="chunked",FRAME-RATE=60.000
+https://video.example.com/chunked.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=1400000,RESOLUTION=852x480,CODECS="avc1.4D401F,mp4a.40.2",FRAME-RATE=30.000
+480p30/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=3000000,RESOLUTION=1280x720,CODECS="hvc1.1.6.L93.B0,mp4a.40.2",FRAME-RATE=60.000
+720p60/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=160000,CODECS="mp4a.40.2"
+audio_only/index.m3u8
+"#;
+
+    #[test]
+    fn test_parse_master_playlist() {
+        let base = Url::parse("https://cdn.example.com/live/master.m3u8?token=1").unwrap();
+        let variants = parse_master_playlist(MASTER, &base);
+
+        let qualities: Vec<_> = variants.iter().map(Variant::quality).collect();
+        assert_eq!(
+            qualities,
+            ["1080p60 (source)", "720p60", "480p", "audio_only"]
+        );
+        assert_eq!(variants[0].url, "https://video.example.com/chunked.m3u8");
+        assert_eq!(
+            variants[1].url,
+            "https://cdn.example.com/live/720p60/index.m3u8"
+        );
+        assert_eq!(variants[1].codec(), Some(Codec::Hevc));
+        assert_eq!(variants[3].codec(), None);
+    }
+}
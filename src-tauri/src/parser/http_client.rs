@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bytes::Bytes;
 use reqwest::{
     header::{CONTENT_TYPE, USER_AGENT},
@@ -34,6 +36,24 @@ impl HttpClient {
         client
     }
 
+    /// 每次请求最长等待 `timeout`，用于请求任意网站
+    pub fn with_timeout(timeout: Duration) -> Self {
+        let mut client = Self::new();
+        client.inner = Client::builder().timeout(timeout).build().unwrap();
+        client
+    }
+
+    /// 不自动跟随重定向，用于逐跳解析短链接，每次请求最长等待 `timeout`
+    pub fn without_redirects(timeout: Duration) -> Self {
+        let mut client = Self::new();
+        client.inner = Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(timeout)
+            .build()
+            .unwrap();
+        client
+    }
+
     pub fn insert_header(&mut self, name: HeaderName, value: &str) -> LsarResult<()> {
         trace!("Inserting header: {:?} = {}", name, value);
         let header_value = HeaderValue::from_str(value).map_err(|e| {
@@ -68,6 +88,15 @@ impl HttpClient {
         Ok(response)
     }
 
+    pub async fn head(&self, url: &str) -> LsarResult<Response> {
+        info!("Sending HEAD request to: {}", url);
+        let response = self.send_request(self.inner.head(url)).await?;
+
+        debug!("HEAD request successful, status: {}", response.status());
+
+        Ok(response)
+    }
+
     pub async fn get_text(&self, url: &str) -> LsarResult<String> {
         let response = self.get(url).await?;
 
@@ -80,6 +109,25 @@ impl HttpClient {
         Ok(body)
     }
 
+    /// 读取响应体开头不超过 `max_bytes` 的部分，避免下载过大的网页或不会结束的直播流
+    pub async fn read_text_limited(mut response: Response, max_bytes: usize) -> LsarResult<String> {
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| {
+            error!("Failed to read response body: {}", e);
+            LsarError::Http(e.into())
+        })? {
+            body.extend_from_slice(&chunk);
+            if body.len() >= max_bytes {
+                debug!("Response body truncated to {} bytes", max_bytes);
+                body.truncate(max_bytes);
+                break;
+            }
+        }
+
+        trace!("Response body received, length: {} bytes", body.len());
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
     pub async fn get_bytes(&self, url: &str) -> LsarResult<Bytes> {
         let response = self.get(url).await?;
 
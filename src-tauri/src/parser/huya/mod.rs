@@ -5,7 +5,9 @@ mod models;
 mod url;
 mod uuid;
 
-use reqwest::header::USER_AGENT;
+use ::url::Url;
+use async_trait::async_trait;
+use reqwest::header::{COOKIE, USER_AGENT};
 use serde_json::Value;
 use url::UrlParser;
 
@@ -15,37 +17,64 @@ use self::login_request::LoginRequest;
 use self::uuid::UuidGenerator;
 
 use crate::{
-    error::{LsarError, LsarResult, MissKeyFieldError, RoomStateError},
+    error::{InputError, LsarError, LsarResult, MissKeyFieldError, RoomStateError},
     parser::time::now,
     platform::Platform,
     utils::md5,
 };
 
-use super::{http_client::HttpClient, ParsedResult, Parser};
+use super::registry::{Capabilities, LiveStatus, ParseContext, Parser, Site};
+use super::room::{path_segments, RoomTarget};
+use super::stream_link::{Protocol, QualityOption, StreamLink, DEFAULT_QUALITY};
+use super::{http_client::HttpClient, ParsedResult};
 
-use self::models::{BaseSteamInfo, CacheProfile};
+use self::models::{BaseSteamInfo, CacheProfile, MultiStreamInfo, ProfileStatus};
 
 const BASE_URL: &str = "https://m.huya.com/";
 
 struct HuyaParser {
     room_id: Option<u64>,
     page_url: String,
+    /// 请求的码率，0 为原画
+    ratio: u32,
+    /// 登录后 cookie 中的用户 ID，未登录时使用匿名登录得到的 ID
+    yyuid: Option<String>,
+    multi_stream_info: Vec<MultiStreamInfo>,
     client: HttpClient,
 }
 
 impl HuyaParser {
-    fn new(room_id: Option<u64>, page_url: String) -> Self {
+    fn new(room_id: Option<u64>, page_url: String, ratio: u32, cookie: &str) -> Self {
         let mut client = HttpClient::new();
         client.insert_header(USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/127.0.0.0 Safari/537.36").unwrap();
 
+        let yyuid = Self::cookie_yyuid(cookie);
+        if !cookie.is_empty() {
+            if let Err(e) = client.insert_header(COOKIE, cookie) {
+                warn!("Ignoring invalid Huya cookie: {}", e);
+            }
+        }
+
         HuyaParser {
             room_id,
             page_url,
+            ratio,
+            yyuid,
+            multi_stream_info: Vec::new(),
             client,
         }
     }
 
-    async fn get_final_room_id(&self) -> LsarResult<u64> {
+    fn cookie_yyuid(cookie: &str) -> Option<String> {
+        cookie
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == "yyuid")
+            .map(|(_, value)| value.to_owned())
+            .filter(|value| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()))
+    }
+
+    async fn get_final_room_id(&mut self) -> LsarResult<u64> {
         info!("Fetching final room ID");
         let url = if let Some(id) = self.room_id {
             format!("{}{}", BASE_URL, id)
@@ -77,6 +106,13 @@ impl HuyaParser {
                 MissKeyFieldError::RoomId
             })?;
 
+        self.multi_stream_info = serde_json::from_value(stream["vMultiStreamInfo"].clone())
+            .unwrap_or_else(|e| {
+                warn!("Failed to parse vMultiStreamInfo: {}", e);
+                Vec::new()
+            });
+        debug!("Available bitrates: {:?}", self.multi_stream_info);
+
         info!("Real room ID: {}", room_id);
         Ok(room_id)
     }
@@ -131,24 +167,62 @@ impl HuyaParser {
                 info!("Room is online");
                 let profile: CacheProfile = serde_json::from_value(profile)?;
 
-                let uid = self.get_anonymous_uid().await?;
+                let uid = match &self.yyuid {
+                    Some(yyuid) => yyuid.clone(),
+                    None => self.get_anonymous_uid().await?,
+                };
                 let links = self
                     .get_stream_links(&profile.data.stream.base_steam_info_list, &uid)
                     .await?;
 
+                let qualities = self
+                    .multi_stream_info
+                    .iter()
+                    .map(|info| QualityOption {
+                        value: info.i_bit_rate,
+                        label: info.s_display_name.clone(),
+                        login_required: false,
+                    })
+                    .collect();
+
                 Ok(ParsedResult {
                     platform: Platform::Huya,
                     links,
                     title: profile.data.live_data.introduction,
                     anchor: profile.data.live_data.nick,
-                    room_id,
+                    room_id: room_id.to_string(),
                     category: profile.data.live_data.game_full_name,
+                    qualities,
+                    current_quality: Some(self.ratio),
+                    cdns: Vec::new(),
+                    current_cdn: None,
+                    notices: Vec::new(),
                 })
             }
             _ => unreachable!(),
         }
     }
 
+    /// 只根据房间信息判断是否在直播，重播不算直播
+    fn profile_status(profile: Value) -> LsarResult<LiveStatus> {
+        let profile: ProfileStatus = serde_json::from_value(profile)?;
+        let live = profile.data.live_status == "ON";
+        debug!("Room live status: {}", profile.data.live_status);
+
+        Ok(match profile.data.live_data {
+            Some(data) => LiveStatus {
+                live,
+                title: data.introduction,
+                viewers: Some(data.user_count).filter(|_| live),
+                start_time: Some(data.start_time).filter(|t| live && *t > 0),
+            },
+            None => LiveStatus {
+                live,
+                ..Default::default()
+            },
+        })
+    }
+
     async fn get_anonymous_uid(&self) -> LsarResult<String> {
         info!("Getting anonymous UID");
         let login_request = LoginRequest::new();
@@ -179,42 +253,66 @@ impl HuyaParser {
         &self,
         base_steam_info_list: &[BaseSteamInfo],
         uid: &str,
-    ) -> LsarResult<Vec<String>> {
-        info!("Getting stream links");
+    ) -> LsarResult<Vec<StreamLink>> {
+        info!("Getting stream links with ratio: {}", self.ratio);
+        let mut items: Vec<&BaseSteamInfo> = base_steam_info_list.iter().collect();
+        // 主线路在前，其余按优先级从高到低
+        items.sort_by_key(|item| {
+            (
+                std::cmp::Reverse(item.i_is_master),
+                std::cmp::Reverse(item.i_web_priority_rate),
+            )
+        });
+
+        let (quality, level) = self.quality();
         let mut links = Vec::new();
 
-        for (index, item) in base_steam_info_list.iter().enumerate() {
-            debug!("Processing steam info item {}", index);
-            if !item.s_flv_anti_code.is_empty() {
-                match self
-                    .parse_anticode(&item.s_flv_anti_code, uid, &item.s_stream_name)
-                    .await
-                {
-                    Ok(anticode) => {
-                        let url = format!(
-                            "{}/{}.{}?{}",
-                            item.s_flv_url, item.s_stream_name, item.s_flv_url_suffix, anticode
-                        );
-                        debug!("Added FLV stream link: {}", url);
-                        links.push(url);
-                    }
-                    Err(e) => error!("Failed to parse FLV anticode: {}", e),
+        for (index, item) in items.into_iter().enumerate() {
+            debug!(
+                "Processing steam info item {}: CDN {}, master {}, priority {}",
+                index, item.s_cdn_type, item.i_is_master, item.i_web_priority_rate
+            );
+            for (protocol, url, anti_code, suffix) in [
+                (
+                    Protocol::Flv,
+                    &item.s_flv_url,
+                    &item.s_flv_anti_code,
+                    &item.s_flv_url_suffix,
+                ),
+                (
+                    Protocol::Hls,
+                    &item.s_hls_url,
+                    &item.s_hls_anti_code,
+                    &item.s_hls_url_suffix,
+                ),
+            ] {
+                if anti_code.is_empty() {
+                    continue;
                 }
-            }
-            if !item.s_hls_anti_code.is_empty() {
+
                 match self
-                    .parse_anticode(&item.s_hls_anti_code, uid, &item.s_stream_name)
+                    .parse_anticode(anti_code, uid, &item.s_stream_name)
                     .await
                 {
-                    Ok(anticode) => {
-                        let url = format!(
-                            "{}/{}.{}?{}",
-                            item.s_hls_url, item.s_stream_name, item.s_hls_url_suffix, anticode
-                        );
-                        debug!("Added HLS stream link: {}", url);
-                        links.push(url);
+                    Ok(mut anticode) => {
+                        if self.ratio != 0 {
+                            anticode.push_str(&format!("&ratio={}", self.ratio));
+                        }
+                        let url = format!("{}/{}.{}?{}", url, item.s_stream_name, suffix, anticode);
+                        debug!("Added {:?} stream link: {}", protocol, url);
+
+                        let mut link = StreamLink::new(url, protocol)
+                            .with_quality(quality, level)
+                            .with_bitrate(self.ratio);
+                        if !item.s_cdn_type.is_empty() {
+                            link = link.with_cdn(&item.s_cdn_type);
+                        }
+                        if item.i_is_master == 1 {
+                            link = link.with_master();
+                        }
+                        links.push(link);
                     }
-                    Err(e) => error!("Failed to parse HLS anticode: {}", e),
+                    Err(e) => error!("Failed to parse {:?} anticode: {}", protocol, e),
                 }
             }
         }
@@ -226,6 +324,19 @@ impl HuyaParser {
         Ok(links)
     }
 
+    /// 请求码率的名称和等级，码率列表按从高到低排列
+    fn quality(&self) -> (&str, u32) {
+        let count = self.multi_stream_info.len();
+        self.multi_stream_info
+            .iter()
+            .position(|info| info.i_bit_rate == self.ratio)
+            .map(|index| {
+                let info = &self.multi_stream_info[index];
+                (info.s_display_name.as_str(), (count - index) as u32)
+            })
+            .unwrap_or((DEFAULT_QUALITY, 0))
+    }
+
     async fn parse_anticode(&self, code: &str, uid: &str, stream_name: &str) -> LsarResult<String> {
         debug!("Parsing anticode for stream: {}", stream_name);
         let mut query = UrlParser::parse_query(code).map_err(|e| {
@@ -278,6 +389,7 @@ impl HuyaParser {
     }
 }
 
+#[async_trait]
 impl Parser for HuyaParser {
     async fn parse(&mut self) -> LsarResult<ParsedResult> {
         info!("Starting Huya parsing process");
@@ -289,14 +401,192 @@ impl Parser for HuyaParser {
     }
 }
 
-#[tauri::command]
-pub async fn parse_huya(room_id: Option<u64>, url: String) -> LsarResult<ParsedResult> {
-    info!("Parsing Huya stream. Room ID: {:?}, URL: {}", room_id, url);
-    let mut huya = HuyaParser::new(room_id, url);
-    let result = huya.parse().await;
-    match &result {
-        Ok(_) => info!("Successfully parsed Huya stream"),
-        Err(e) => error!("Failed to parse Huya stream: {}", e),
+pub(crate) struct HuyaSite;
+
+#[async_trait]
+impl Site for HuyaSite {
+    fn platform(&self) -> Platform {
+        Platform::Huya
+    }
+
+    fn label(&self) -> &'static str {
+        "虎牙"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        "https://www.huya.com/"
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["huya.com"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            room_name: true,
+            live_status: true,
+            ..Default::default()
+        }
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        let segments = path_segments(url);
+        let first = segments.first().ok_or(InputError::InvalidRoomId)?;
+
+        Ok(match first.parse::<u64>() {
+            Ok(room_id) => RoomTarget::new(Platform::Huya, room_id),
+            // 自定义的房间名需要从网页中获取房间号
+            Err(_) => RoomTarget::with_url(Platform::Huya, url),
+        })
+    }
+
+    fn parser(&self, target: RoomTarget, ctx: &ParseContext) -> Box<dyn Parser> {
+        let room_id = Some(target.numeric_id()).filter(|id| *id != 0);
+        Box::new(HuyaParser::new(
+            room_id,
+            target.url.unwrap_or_default(),
+            ctx.options.quality.unwrap_or(0),
+            ctx.config.huya_cookie(),
+        ))
+    }
+
+    async fn status(&self, target: RoomTarget, ctx: &ParseContext) -> LsarResult<LiveStatus> {
+        let room_id = Some(target.numeric_id()).filter(|id| *id != 0);
+        let mut parser = HuyaParser::new(
+            room_id,
+            target.url.unwrap_or_default(),
+            0,
+            ctx.config.huya_cookie(),
+        );
+
+        // 自定义的房间名需要先从网页中获取房间号
+        let room_id = match room_id {
+            Some(room_id) => room_id,
+            None => parser.get_final_room_id().await?,
+        };
+        let profile = parser.get_room_profile(room_id).await?;
+        HuyaParser::profile_status(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_from_multi_stream_info() {
+        let mut parser = HuyaParser::new(Some(1), String::new(), 4000, "");
+        parser.multi_stream_info = serde_json::from_str(
+            r#"[
+                {"sDisplayName": "原画", "iBitRate": 0},
+                {"sDisplayName": "蓝光10M", "iBitRate": 10000},
+                {"sDisplayName": "蓝光4M", "iBitRate": 4000},
+                {"sDisplayName": "超清", "iBitRate": 2000}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(parser.quality(), ("蓝光4M", 2));
+
+        parser.ratio = 0;
+        assert_eq!(parser.quality(), ("原画", 4));
+
+        parser.ratio = 500;
+        assert_eq!(parser.quality(), (DEFAULT_QUALITY, 0));
+    }
+
+    #[tokio::test]
+    async fn test_stream_links_order_and_labels() {
+        let info: Value = serde_json::from_str(include_str!(
+            "../../../tests/fixtures/huya/stream_info.json"
+        ))
+        .unwrap();
+        let mut parser = HuyaParser::new(Some(1), String::new(), 4000, "");
+        parser.multi_stream_info =
+            serde_json::from_value(info["vMultiStreamInfo"].clone()).unwrap();
+        let base_steam_info_list: Vec<BaseSteamInfo> =
+            serde_json::from_value(info["baseSteamInfoList"].clone()).unwrap();
+
+        let links = parser
+            .get_stream_links(&base_steam_info_list, "1234567890")
+            .await
+            .unwrap();
+        let links: Vec<Value> = links
+            .iter()
+            .map(|link| serde_json::to_value(link).unwrap())
+            .collect();
+
+        // 主线路在前，其余按优先级从高到低，没有防盗链参数的 HLS 链接被跳过
+        let order: Vec<(&str, &str, bool)> = links
+            .iter()
+            .map(|link| {
+                (
+                    link["cdn"].as_str().unwrap(),
+                    link["protocol"].as_str().unwrap(),
+                    link["master"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("TX", "flv", true),
+                ("TX", "hls", true),
+                ("AL", "flv", false),
+                ("AL", "hls", false),
+                ("HW", "flv", false),
+            ]
+        );
+
+        for link in &links {
+            assert_eq!(link["quality"], "蓝光4M");
+            assert_eq!(link["level"], 3);
+            assert_eq!(link["bitrate"], 4000);
+            assert!(link["url"].as_str().unwrap().contains("ratio=4000"));
+        }
+        assert!(links[0]["url"]
+            .as_str()
+            .unwrap()
+            .starts_with("https://tx.flv.huya.com/src/1394575534-1394575534-5989656310331736064-2789274524-10057-A-0-1.flv?"));
+    }
+
+    #[test]
+    fn test_cookie_yyuid() {
+        assert_eq!(
+            HuyaParser::cookie_yyuid("udb_uid=1; yyuid=1234567890; __yamid_tt1=0.1"),
+            Some("1234567890".to_owned())
+        );
+        assert_eq!(HuyaParser::cookie_yyuid("yyuid=; udb_uid=1"), None);
+        assert_eq!(HuyaParser::cookie_yyuid(""), None);
+    }
+
+    #[test]
+    fn test_profile_status() {
+        let live: Value = serde_json::from_str(include_str!(
+            "../../../tests/fixtures/huya/profile_room_live.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            HuyaParser::profile_status(live).unwrap(),
+            LiveStatus {
+                live: true,
+                title: "今晚冲分，不上王者不下播".to_owned(),
+                viewers: Some(1285634),
+                start_time: Some(1760763600),
+            }
+        );
+
+        let replay: Value = serde_json::from_str(include_str!(
+            "../../../tests/fixtures/huya/profile_room_replay.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            HuyaParser::profile_status(replay).unwrap(),
+            LiveStatus {
+                live: false,
+                title: "精彩回放".to_owned(),
+                viewers: None,
+                start_time: None,
+            }
+        );
     }
-    result
 }
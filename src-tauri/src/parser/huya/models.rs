@@ -21,6 +21,14 @@ pub(super) struct StreamInfo {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all(deserialize = "camelCase"))]
 pub(super) struct BaseSteamInfo {
+    /// CDN 名称，如 AL、TX、HW、HS
+    #[serde(default)]
+    pub(super) s_cdn_type: String,
+    #[serde(default)]
+    pub(super) i_is_master: i32,
+    /// 网页端的线路优先级，越大越优先
+    #[serde(default)]
+    pub(super) i_web_priority_rate: i32,
     pub(super) s_stream_name: String,
     pub(super) s_flv_url: String,
     pub(super) s_flv_anti_code: String,
@@ -37,3 +45,37 @@ pub(super) struct LiveData {
     pub(super) game_full_name: String,
     pub(super) introduction: String,
 }
+
+/// `profileRoom` 接口中查询直播状态需要的字段，未开播时 `liveData` 为上次直播的信息
+#[derive(Debug, Deserialize)]
+pub(super) struct ProfileStatus {
+    pub(super) data: ProfileStatusData,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct ProfileStatusData {
+    /// ON、OFF 或 REPLAY
+    pub(super) live_status: String,
+    pub(super) live_data: Option<StatusLiveData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct StatusLiveData {
+    #[serde(default)]
+    pub(super) introduction: String,
+    #[serde(default)]
+    pub(super) user_count: u64,
+    /// 开播时间，单位秒
+    #[serde(default)]
+    pub(super) start_time: i64,
+}
+
+/// 网页中 `vMultiStreamInfo` 的码率信息，`i_bit_rate` 为 0 时即原画
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct MultiStreamInfo {
+    pub(super) s_display_name: String,
+    pub(super) i_bit_rate: u32,
+}
@@ -0,0 +1,256 @@
+mod models;
+
+use async_trait::async_trait;
+use url::Url;
+
+use crate::error::{InputError, LsarResult, MissKeyFieldError, RoomStateError};
+use crate::parser::registry::{Capabilities, ParseContext, Parser, Site};
+use crate::parser::room::{path_segments, RoomTarget};
+use crate::parser::stream_link::{Codec, Protocol, StreamLink};
+use crate::parser::ParsedResult;
+use crate::platform::Platform;
+
+use self::models::{InitialState, PlayItem};
+
+use super::http_client::HttpClient;
+
+const BASE_URL: &str = "https://live.kuaishou.com/u/";
+
+pub struct KuaishouParser {
+    page_url: String,
+    client: HttpClient,
+}
+
+impl KuaishouParser {
+    pub fn new(page_url: String) -> Self {
+        KuaishouParser {
+            page_url,
+            client: HttpClient::new(),
+        }
+    }
+
+    fn extract_initial_state(html: &str) -> LsarResult<InitialState> {
+        trace!("Extracting __INITIAL_STATE__ from HTML");
+        let json = html
+            .split("window.__INITIAL_STATE__=")
+            .nth(1)
+            .and_then(|s| s.split(";(function(").next())
+            .ok_or_else(|| {
+                error!("Failed to find __INITIAL_STATE__ in page");
+                MissKeyFieldError::PageState
+            })?;
+
+        // 网页中的状态不是严格的 json，其中有 undefined
+        let json = json.replace(":undefined", ":null");
+        let state: InitialState = serde_json::from_str(&json).map_err(|e| {
+            error!("Failed to parse __INITIAL_STATE__: {}", e);
+            e
+        })?;
+
+        debug!("Parsed initial state: {:?}", state);
+        Ok(state)
+    }
+
+    fn parse_page(html: &str) -> LsarResult<ParsedResult> {
+        let state = Self::extract_initial_state(html)?;
+        let mut play_list = state.liveroom.play_list;
+        let index = state.liveroom.active_index;
+        if index >= play_list.len() {
+            warn!("Play list is empty, the room may not exist");
+            return Err(RoomStateError::NotExists.into());
+        }
+        let item = play_list.swap_remove(index);
+
+        let Some(author) = item.author.as_ref().filter(|_| item.live_exist) else {
+            info!("Author not found or live room does not exist");
+            return Err(RoomStateError::NotExists.into());
+        };
+        if !item.is_living {
+            info!("Room is offline: {}", author.id);
+            return Err(RoomStateError::Offline.into());
+        }
+
+        let links = Self::stream_links(&item);
+        if links.is_empty() {
+            warn!("Room is living but no play URL was found");
+            return Err(RoomStateError::Offline.into());
+        }
+
+        let title = item
+            .live_stream
+            .as_ref()
+            .map(|s| s.caption.clone())
+            .filter(|caption| !caption.is_empty())
+            .unwrap_or_else(|| format!("{}的直播", author.name));
+        let category = item
+            .game_info
+            .as_ref()
+            .map(|g| g.name.clone())
+            .unwrap_or_default();
+
+        Ok(ParsedResult {
+            platform: Platform::Kuaishou,
+            title,
+            anchor: author.name.clone(),
+            room_id: author.id.clone(),
+            category,
+            links,
+            qualities: Vec::new(),
+            current_quality: None,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices: Vec::new(),
+        })
+    }
+
+    /// 所有编码的所有清晰度，清晰度从高到低
+    fn stream_links(item: &PlayItem) -> Vec<StreamLink> {
+        let Some(live_stream) = &item.live_stream else {
+            return Vec::new();
+        };
+
+        let mut representations: Vec<_> = live_stream
+            .play_urls
+            .iter()
+            .flat_map(|play_url| {
+                play_url
+                    .adaptation_set
+                    .representation
+                    .iter()
+                    .map(move |r| (r, Codec::from_name(&play_url.codec)))
+            })
+            .collect();
+        representations.sort_by_key(|(r, _)| std::cmp::Reverse(r.level));
+
+        representations
+            .into_iter()
+            .map(|(r, codec)| {
+                trace!("Found {} ({:?}) stream: {}", r.name, codec, r.url);
+                StreamLink::new(
+                    r.url.clone(),
+                    Protocol::from_url(&r.url).unwrap_or(Protocol::Flv),
+                )
+                .with_quality(r.name.as_str(), r.level)
+                .with_codec(codec)
+                .with_bitrate(r.bitrate)
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Parser for KuaishouParser {
+    async fn parse(&mut self) -> LsarResult<ParsedResult> {
+        info!("Starting Kuaishou parsing process: {}", self.page_url);
+
+        let html = self.client.get_text(&self.page_url).await.map_err(|e| {
+            error!("Failed to fetch room page: {}", e);
+            e
+        })?;
+
+        let result = Self::parse_page(&html);
+        info!("Kuaishou parsing process completed");
+        result
+    }
+}
+
+pub(crate) struct KuaishouSite;
+
+impl Site for KuaishouSite {
+    fn platform(&self) -> Platform {
+        Platform::Kuaishou
+    }
+
+    fn label(&self) -> &'static str {
+        "快手"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        BASE_URL
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["live.kuaishou.com"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            room_name: true,
+            ..Default::default()
+        }
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        match path_segments(url).as_slice() {
+            ["u", id, ..] => {
+                let url = Url::parse(&format!("{}{}", BASE_URL, id))?;
+                Ok(RoomTarget::with_url(Platform::Kuaishou, &url))
+            }
+            _ => Err(InputError::InvalidRoomId.into()),
+        }
+    }
+
+    fn parser(&self, target: RoomTarget, _ctx: &ParseContext) -> Box<dyn Parser> {
+        // 纯数字的主播 ID 经简写输入时没有链接
+        let page_url = target
+            .url
+            .unwrap_or_else(|| format!("{}{}", BASE_URL, target.room_id));
+        Box::new(KuaishouParser::new(page_url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIVE_ROOM: &str = include_str!("../../../tests/fixtures/kuaishou/live_room.html");
+    const OFFLINE_ROOM: &str = include_str!("../../../tests/fixtures/kuaishou/offline_room.html");
+
+    #[test]
+    fn test_parse_live_room() {
+        let result = KuaishouParser::parse_page(LIVE_ROOM).unwrap();
+        assert_eq!(result.platform, Platform::Kuaishou);
+        assert_eq!(result.room_id, "3xk5m7f8ptdsnqw");
+        assert_eq!(result.title, "今晚冲分 上王者");
+        assert_eq!(result.anchor, "快手测试主播");
+        assert_eq!(result.category, "王者荣耀");
+
+        // h264 四种清晰度和 hevc 两种清晰度
+        assert_eq!(result.links.len(), 6);
+        let link = serde_json::to_value(&result.links[0]).unwrap();
+        assert_eq!(link["quality"], "蓝光 8M");
+        assert_eq!(link["protocol"], "flv");
+        assert_eq!(link["bitrate"], 8000);
+        assert_eq!(link["codec"], "avc");
+    }
+
+    #[test]
+    fn test_parse_offline_room() {
+        assert!(matches!(
+            KuaishouParser::parse_page(OFFLINE_ROOM),
+            Err(crate::error::LsarError::RoomState(RoomStateError::Offline))
+        ));
+        assert!(matches!(
+            KuaishouParser::parse_page("<html></html>"),
+            Err(crate::error::LsarError::MissKeyField(
+                MissKeyFieldError::PageState
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_room_from_url() {
+        let target =
+            RoomTarget::from_input("https://live.kuaishou.com/u/3xk5m7f8ptdsnqw?from=share")
+                .unwrap();
+        assert_eq!(target.platform, Platform::Kuaishou);
+        assert_eq!(
+            target.url.as_deref(),
+            Some("https://live.kuaishou.com/u/3xk5m7f8ptdsnqw")
+        );
+        assert_eq!(
+            RoomTarget::from_input("kuaishou:3xk5m7f8ptdsnqw").unwrap(),
+            target
+        );
+    }
+}
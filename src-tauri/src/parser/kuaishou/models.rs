@@ -0,0 +1,79 @@
+use serde::Deserialize;
+
+/// 直播间网页中 `window.__INITIAL_STATE__` 的部分字段
+#[derive(Debug, Deserialize)]
+pub(super) struct InitialState {
+    pub(super) liveroom: LiveRoom,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct LiveRoom {
+    #[serde(default)]
+    pub(super) active_index: usize,
+    #[serde(default)]
+    pub(super) play_list: Vec<PlayItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct PlayItem {
+    pub(super) live_stream: Option<LiveStream>,
+    pub(super) author: Option<Author>,
+    pub(super) game_info: Option<GameInfo>,
+    #[serde(default)]
+    pub(super) is_living: bool,
+    #[serde(default = "default_live_exist")]
+    pub(super) live_exist: bool,
+}
+
+fn default_live_exist() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct LiveStream {
+    #[serde(default)]
+    pub(super) caption: String,
+    #[serde(default)]
+    pub(super) play_urls: Vec<PlayUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct PlayUrl {
+    /// 编码，h264 或 hevc
+    #[serde(default, rename = "type")]
+    pub(super) codec: String,
+    pub(super) adaptation_set: AdaptationSet,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct AdaptationSet {
+    #[serde(default)]
+    pub(super) representation: Vec<Representation>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Representation {
+    pub(super) url: String,
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) level: u32,
+    /// 码率，单位 kbps
+    #[serde(default)]
+    pub(super) bitrate: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Author {
+    pub(super) id: String,
+    pub(super) name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GameInfo {
+    #[serde(default)]
+    pub(super) name: String,
+}
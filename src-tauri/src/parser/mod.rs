@@ -1,30 +1,101 @@
+mod acfun;
 mod bilibili;
+mod cc;
 mod douyin;
 mod douyu;
+mod generic;
+mod hls;
 mod http_client;
 mod huya;
+mod kuaishou;
+mod registry;
+mod room;
+mod share_link;
+mod status;
+mod stream_link;
+#[cfg(test)]
+mod test_server;
 mod time;
+mod twitch;
+mod youtube;
 
 use serde::Serialize;
 
-pub use self::bilibili::parse_bilibili;
-pub use self::douyin::parse_douyin;
-pub use self::douyu::parse_douyu;
-pub use self::huya::parse_huya;
+pub use self::bilibili::{get_bilibili_login_qrcode, poll_bilibili_login};
+pub use self::registry::list_platforms;
+pub(crate) use self::registry::{LiveStatus, ParseContext, Platform};
+pub use self::status::check_live_status;
+pub(crate) use self::status::{room_status, supports_live_status};
 
-use crate::{error::LsarResult, platform::Platform};
+use self::registry::{ParseOptions, Parser};
+use self::stream_link::{CdnOption, QualityOption, StreamLink};
+use crate::config::read_config_file;
+use crate::error::LsarResult;
+use crate::eval::EvalChannel;
 
 #[derive(Debug, Serialize)]
 pub struct ParsedResult {
     platform: Platform,
     title: String,
     anchor: String,
+    /// 房间号，部分平台为主播的字符串 ID
     #[serde(rename(serialize = "roomID"))]
-    room_id: u64,
+    room_id: String,
     category: String,
-    links: Vec<String>,
+    links: Vec<StreamLink>,
+    /// 可切换的清晰度，平台不支持切换时为空
+    qualities: Vec<QualityOption>,
+    #[serde(rename(serialize = "currentQuality"))]
+    current_quality: Option<u32>,
+    /// 可切换的 CDN，平台不支持切换时为空
+    cdns: Vec<CdnOption>,
+    #[serde(rename(serialize = "currentCDN"))]
+    current_cdn: Option<String>,
+    /// 需要提示用户的信息，如未登录时部分清晰度不可用
+    notices: Vec<String>,
 }
 
-trait Parser {
-    async fn parse(&mut self) -> LsarResult<ParsedResult>;
+/// 统一的解析入口，自动识别平台和房间号
+#[tauri::command]
+pub async fn parse_url(
+    input: String,
+    options: Option<ParseOptions>,
+    eval_channel: tauri::State<'_, EvalChannel>,
+    app_handle: tauri::AppHandle,
+) -> LsarResult<ParsedResult> {
+    info!("Parsing input: {}, options: {:?}", input, options);
+
+    let share_link::Resolved { target, page } = share_link::resolve(&input).await.map_err(|e| {
+        error!("Failed to resolve room from input: {}", e);
+        e
+    })?;
+    info!(
+        "Resolved platform: {}, room ID: {}, URL: {:?}",
+        target.platform.to_str(),
+        target.room_id,
+        target.url
+    );
+
+    let ctx = ParseContext {
+        config: read_config_file().await?,
+        options: options.unwrap_or_default(),
+        eval_channel: eval_channel.inner().clone(),
+        app_handle,
+    };
+
+    if target.platform == Platform::Generic && generic::is_media_url(&target.room_id) {
+        return generic::play_media_url(&ctx.config, &target.room_id).await;
+    }
+
+    let mut parser: Box<dyn Parser> = match page {
+        Some(page) => Box::new(generic::GenericParser::from_page(target.room_id, page)),
+        None => registry::site(target.platform).parser(target, &ctx),
+    };
+    let result = parser.parse().await;
+
+    match &result {
+        Ok(_) => info!("Successfully parsed input: {}", input),
+        Err(e) => error!("Failed to parse input: {}. Error: {}", input, e),
+    }
+    result
 }
@@ -0,0 +1,177 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::acfun::AcFunSite;
+use super::bilibili::BilibiliSite;
+use super::cc::CCSite;
+use super::douyin::DouyinSite;
+use super::douyu::DouyuSite;
+use super::generic::GenericSite;
+use super::huya::HuyaSite;
+use super::kuaishou::KuaishouSite;
+use super::room::RoomTarget;
+use super::stream_link::{Codec, Protocol};
+use super::twitch::TwitchSite;
+use super::youtube::YouTubeSite;
+use super::ParsedResult;
+use crate::config::Config;
+use crate::error::{LsarError, LsarResult};
+use crate::eval::EvalChannel;
+use crate::platform::platforms;
+
+platforms! {
+    Douyu => "douyu": DouyuSite,
+    Huya => "huya": HuyaSite,
+    Douyin => "douyin": DouyinSite,
+    Bilibili => "bilibili": BilibiliSite,
+    Kuaishou => "kuaishou": KuaishouSite,
+    CC => "cc": CCSite,
+    Twitch => "twitch": TwitchSite,
+    YouTube => "youtube": YouTubeSite,
+    AcFun => "acfun": AcFunSite,
+    Generic => "generic": GenericSite,
+}
+
+/// 单次解析，每次解析都会创建新的实例
+#[async_trait]
+pub(crate) trait Parser: Send {
+    async fn parse(&mut self) -> LsarResult<ParsedResult>;
+}
+
+/// 平台解析器在解析前需要的额外条件
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Capabilities {
+    /// 解析时使用配置中的 cookie
+    pub(crate) cookie: bool,
+    /// 解析时需要在 webview 中执行 js
+    pub(crate) eval: bool,
+    /// 房间可以用非数字的名称表示
+    pub(crate) room_name: bool,
+    /// 有只查询直播状态的轻量接口，覆盖了 `Site::status`
+    pub(crate) live_status: bool,
+}
+
+/// 用户在解析时指定的偏好，平台不支持的选项会被忽略
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseOptions {
+    /// 清晰度，取值为上次解析结果中 `qualities` 的 `value`
+    pub(crate) quality: Option<u32>,
+    pub(crate) protocol: Option<Protocol>,
+    pub(crate) codec: Option<Codec>,
+    /// CDN，取值为上次解析结果中 `cdns` 的 `value`
+    pub(crate) cdn: Option<String>,
+}
+
+pub(crate) struct ParseContext {
+    pub(crate) config: Config,
+    pub(crate) options: ParseOptions,
+    pub(crate) eval_channel: EvalChannel,
+    pub(crate) app_handle: tauri::AppHandle,
+}
+
+/// 房间当前的直播状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LiveStatus {
+    pub(crate) live: bool,
+    /// 未开播时部分平台返回上次直播的标题
+    pub(crate) title: String,
+    /// 观看人数或人气值，平台未提供时为空
+    pub(crate) viewers: Option<u64>,
+    /// 开播时间的 Unix 时间戳，单位秒，未开播或平台未提供时为空
+    pub(crate) start_time: Option<i64>,
+}
+
+/// 一个直播平台在注册表中的登记信息
+#[async_trait]
+pub(crate) trait Site: Send + Sync {
+    fn platform(&self) -> Platform;
+
+    fn label(&self) -> &'static str;
+
+    /// 房间号拼接在此链接之后即为直播间链接
+    fn room_base_url(&self) -> &'static str;
+
+    /// 属于此平台的域名，子域名也会被匹配
+    fn url_patterns(&self) -> &'static [&'static str];
+
+    fn capabilities(&self) -> Capabilities;
+
+    /// 从已匹配域名的链接中获取房间
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget>;
+
+    fn parser(&self, target: RoomTarget, ctx: &ParseContext) -> Box<dyn Parser>;
+
+    /// 查询房间是否在直播，只请求平台查询状态的轻量接口。
+    /// 完整解析会获取直播流甚至执行 js，不适合频繁查询，没有轻量接口的平台不支持查询
+    async fn status(&self, _target: RoomTarget, _ctx: &ParseContext) -> LsarResult<LiveStatus> {
+        Err(LsarError::Other(format!(
+            "暂不支持查询{}的直播状态",
+            self.label()
+        )))
+    }
+
+    fn matches_host(&self, host: &str) -> bool {
+        self.url_patterns()
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+    }
+}
+
+pub(crate) fn site(platform: Platform) -> &'static dyn Site {
+    *SITES
+        .iter()
+        .find(|s| s.platform() == platform)
+        .expect("every platform must be registered")
+}
+
+pub(crate) fn site_for_host(host: &str) -> Option<&'static dyn Site> {
+    SITES.iter().find(|s| s.matches_host(host)).copied()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformInfo {
+    key: Platform,
+    label: &'static str,
+    #[serde(rename = "roomBaseURL")]
+    room_base_url: &'static str,
+    capabilities: Capabilities,
+}
+
+#[tauri::command]
+pub async fn list_platforms() -> Vec<PlatformInfo> {
+    SITES
+        .iter()
+        .map(|s| PlatformInfo {
+            key: s.platform(),
+            label: s.label(),
+            room_base_url: s.room_base_url(),
+            capabilities: s.capabilities(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_platform_is_registered_once() {
+        for s in SITES {
+            let platform = s.platform();
+            assert_eq!(site(platform).platform(), platform);
+            assert_eq!(Platform::try_from(platform.to_str()), Ok(platform));
+            assert!(s.url_patterns().iter().all(|p| s.matches_host(p)));
+        }
+
+        assert_eq!(
+            site_for_host("m.douyu.com").map(|s| s.platform()),
+            Some(Platform::Douyu)
+        );
+        assert!(site_for_host("notdouyu.com").is_none());
+    }
+}
@@ -0,0 +1,246 @@
+use url::Url;
+
+use super::registry;
+use crate::error::{InputError, LsarResult};
+use crate::platform::Platform;
+
+/// 从用户输入中识别出的直播间
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct RoomTarget {
+    pub(crate) platform: Platform,
+    /// 房间号或主播 ID，无法直接从输入中得到时为空，由解析器从网页中获取
+    pub(crate) room_id: String,
+    /// 房间号需要从网页中获取时使用的链接
+    pub(crate) url: Option<String>,
+}
+
+impl RoomTarget {
+    pub(crate) fn new(platform: Platform, room_id: impl ToString) -> Self {
+        RoomTarget {
+            platform,
+            room_id: room_id.to_string(),
+            url: None,
+        }
+    }
+
+    pub(crate) fn with_url(platform: Platform, url: &Url) -> Self {
+        RoomTarget {
+            platform,
+            room_id: String::new(),
+            url: Some(url.to_string()),
+        }
+    }
+
+    /// 数字房间号，房间号未知或不是数字时为 0
+    pub(crate) fn numeric_id(&self) -> u64 {
+        self.room_id.parse().unwrap_or(0)
+    }
+
+    /// 支持完整链接、移动端链接、省略协议的链接和 `平台:房间号` 简写。
+    pub(crate) fn from_input(input: &str) -> LsarResult<Self> {
+        let input = input.trim();
+        trace!("Resolving room target from input: {}", input);
+
+        if input.is_empty() {
+            return Err(InputError::InvalidUrl.into());
+        }
+
+        if let Some(target) = Self::from_shorthand(input)? {
+            debug!("Resolved shorthand input: {:?}", target);
+            return Ok(target);
+        }
+
+        let url = url_from_input(input)?;
+        let target = Self::from_url(&url)?;
+        debug!("Resolved URL input: {:?}", target);
+        Ok(target)
+    }
+
+    fn from_shorthand(input: &str) -> LsarResult<Option<Self>> {
+        let Some((key, room)) = input.split_once(':').or_else(|| input.split_once('：')) else {
+            return Ok(None);
+        };
+
+        // `https://...` 之类的链接也包含冒号
+        if room.starts_with("//") {
+            return Ok(None);
+        }
+
+        match Platform::try_from(key.trim().to_lowercase().as_str()) {
+            Ok(platform) => Self::from_room(platform, room.trim()).map(Some),
+            // 省略协议的 `主机:端口/路径` 按链接处理
+            Err(_) if url_from_input(input).is_ok() => Ok(None),
+            Err(_) => Err(InputError::UnknownPlatform.into()),
+        }
+    }
+
+    /// 已知平台的房间号或房间名
+    pub(crate) fn from_room(platform: Platform, room: &str) -> LsarResult<Self> {
+        let site = registry::site(platform);
+
+        // 没有房间号概念的平台只能输入链接
+        if site.room_base_url().is_empty() {
+            return Err(InputError::InvalidRoomId.into());
+        }
+
+        match room.parse::<u64>() {
+            Ok(room_id) => Ok(Self::new(platform, room_id)),
+            Err(_) if site.capabilities().room_name && is_room_name(room) => {
+                let url = Url::parse(&format!("{}{}", site.room_base_url(), room))?;
+                site.room_from_url(&url)
+            }
+            Err(_) => Err(InputError::InvalidRoomId.into()),
+        }
+    }
+
+    fn from_url(url: &Url) -> LsarResult<Self> {
+        let host = url.host_str().ok_or(InputError::InvalidUrl)?;
+
+        let site = registry::site_for_host(host).unwrap_or_else(|| {
+            info!(
+                "No parser for host {}, falling back to generic parser",
+                host
+            );
+            registry::site(Platform::Generic)
+        });
+
+        site.room_from_url(url)
+    }
+}
+
+/// 允许省略协议的链接
+pub(crate) fn url_from_input(input: &str) -> LsarResult<Url> {
+    let input = input.trim();
+    if input.contains("://") {
+        Ok(Url::parse(input)?)
+    } else if !input.contains('.') {
+        // 纯数字等无法识别的输入也能被解析为 ip 地址
+        Err(InputError::InvalidUrl.into())
+    } else {
+        Url::parse(&format!("https://{}", input)).map_err(|_| InputError::InvalidUrl.into())
+    }
+}
+
+/// 链接路径中非空的部分
+pub(crate) fn path_segments(url: &Url) -> Vec<&str> {
+    url.path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+fn is_room_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(input: &str) -> RoomTarget {
+        RoomTarget::from_input(input).unwrap()
+    }
+
+    #[test]
+    fn test_douyu_inputs() {
+        let expected = RoomTarget::new(Platform::Douyu, 9999);
+        assert_eq!(resolve("https://www.douyu.com/9999"), expected);
+        assert_eq!(resolve("https://m.douyu.com/9999?from=share"), expected);
+        assert_eq!(resolve("www.douyu.com/9999"), expected);
+        assert_eq!(
+            resolve("https://www.douyu.com/topic/s14?rid=9999&dyshid=0"),
+            expected
+        );
+        assert_eq!(resolve("douyu:9999"), expected);
+        assert_eq!(resolve(" Douyu：9999 "), expected);
+    }
+
+    #[test]
+    fn test_huya_inputs() {
+        assert_eq!(
+            resolve("https://www.huya.com/660000"),
+            RoomTarget::new(Platform::Huya, 660000)
+        );
+        assert_eq!(
+            resolve("https://m.huya.com/660000"),
+            RoomTarget::new(Platform::Huya, 660000)
+        );
+
+        let named = resolve("https://www.huya.com/kpl");
+        assert_eq!(named.platform, Platform::Huya);
+        assert!(named.room_id.is_empty());
+        assert_eq!(named.url.as_deref(), Some("https://www.huya.com/kpl"));
+
+        assert_eq!(resolve("huya:kpl"), named);
+    }
+
+    #[test]
+    fn test_bilibili_inputs() {
+        let expected = RoomTarget::new(Platform::Bilibili, 21452505);
+        assert_eq!(resolve("https://live.bilibili.com/21452505"), expected);
+        assert_eq!(resolve("https://live.bilibili.com/h5/21452505"), expected);
+        assert_eq!(
+            resolve("https://live.bilibili.com/blanc/21452505?liteVersion=true"),
+            expected
+        );
+        assert_eq!(resolve("bilibili:21452505"), expected);
+    }
+
+    #[test]
+    fn test_douyin_inputs() {
+        let expected = RoomTarget::new(Platform::Douyin, "646454278948");
+        assert_eq!(resolve("https://live.douyin.com/646454278948"), expected);
+        assert_eq!(
+            resolve("https://live.douyin.com/646454278948?anchor_id=1"),
+            expected
+        );
+        assert_eq!(resolve("douyin:646454278948"), expected);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert_eq!(
+            RoomTarget::from_input("https://example.com/123").unwrap(),
+            RoomTarget {
+                platform: Platform::Generic,
+                room_id: "https://example.com/123".to_owned(),
+                url: Some("https://example.com/123".to_owned()),
+            }
+        );
+        assert!(matches!(
+            RoomTarget::from_input("generic:123"),
+            Err(crate::error::LsarError::Input(InputError::InvalidRoomId))
+        ));
+        assert!(matches!(
+            RoomTarget::from_input("unknown:123"),
+            Err(crate::error::LsarError::Input(InputError::UnknownPlatform))
+        ));
+        assert!(matches!(
+            RoomTarget::from_input("douyu:abc"),
+            Err(crate::error::LsarError::Input(InputError::InvalidRoomId))
+        ));
+        assert!(matches!(
+            RoomTarget::from_input("https://live.douyin.com/"),
+            Err(crate::error::LsarError::Input(InputError::InvalidRoomId))
+        ));
+        assert!(RoomTarget::from_input("").is_err());
+        assert!(RoomTarget::from_input("9999").is_err());
+    }
+
+    #[test]
+    fn test_host_with_port() {
+        assert_eq!(
+            resolve("192.168.1.2:8080/live.m3u8"),
+            RoomTarget {
+                platform: Platform::Generic,
+                room_id: "https://192.168.1.2:8080/live.m3u8".to_owned(),
+                url: Some("https://192.168.1.2:8080/live.m3u8".to_owned()),
+            }
+        );
+        assert_eq!(
+            resolve("localhost:8080/live.flv").platform,
+            Platform::Generic
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use url::Url;
+
+use super::generic::{is_media_url, FetchedPage, MAX_PAGE_BYTES};
+use super::http_client::HttpClient;
+use super::registry;
+use super::room::{url_from_input, RoomTarget};
+use crate::error::{InputError, LsarError, LsarResult};
+use crate::platform::Platform;
+
+/// 属于直播平台域名但路径不是房间号的分享域名
+const SHARE_HOSTS: &[&str] = &["v.douyin.com", "v.kuaishou.com", "hd.huya.com"];
+
+/// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 10;
+
+/// 单次请求的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+static OG_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<meta[^>]+property=["']og:url["'][^>]+content=["']([^"']+)["']"#).unwrap()
+});
+
+/// 识别出的直播间，以及识别过程中已下载的网页
+#[derive(Debug)]
+pub(crate) struct Resolved {
+    pub(crate) target: RoomTarget,
+    /// 无法识别平台的网页，通用解析器直接使用，不再重复请求
+    pub(crate) page: Option<FetchedPage>,
+}
+
+impl From<RoomTarget> for Resolved {
+    fn from(target: RoomTarget) -> Self {
+        Resolved { target, page: None }
+    }
+}
+
+/// 跟随分享链接的结果
+enum Followed {
+    Room(RoomTarget),
+    /// 最后一跳是无法识别平台的网页
+    Page(FetchedPage),
+    Unresolved,
+}
+
+/// 识别用户输入的直播间，短链接和分享链接会先解析出真实的直播间链接
+pub(crate) async fn resolve(input: &str) -> LsarResult<Resolved> {
+    ShareLinkResolver::new().resolve(input).await
+}
+
+struct ShareLinkResolver {
+    client: HttpClient,
+}
+
+impl ShareLinkResolver {
+    fn new() -> Self {
+        ShareLinkResolver {
+            client: HttpClient::without_redirects(REQUEST_TIMEOUT),
+        }
+    }
+
+    async fn resolve(&self, input: &str) -> LsarResult<Resolved> {
+        let target = RoomTarget::from_input(input);
+        match &target {
+            Ok(t) if t.platform != Platform::Generic && !is_share_link(t.url.as_deref()) => {
+                return target.map(Resolved::from);
+            }
+            // 直播流链接直接播放，请求它会一直下载流
+            Ok(t) if t.platform == Platform::Generic && is_media_url(&t.room_id) => {
+                return target.map(Resolved::from);
+            }
+            Ok(_) | Err(LsarError::Input(InputError::InvalidRoomId)) => {}
+            Err(_) => return target.map(Resolved::from),
+        }
+
+        let Ok(url) = url_from_input(input) else {
+            return target.map(Resolved::from);
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return target.map(Resolved::from);
+        }
+
+        match self.follow(url).await {
+            Ok(Followed::Room(resolved)) => {
+                info!("Resolved share link {} to {:?}", input, resolved);
+                Ok(resolved.into())
+            }
+            // 只有通用解析器需要网页，已知平台的链接无法解析时按原样交给平台解析器
+            Ok(Followed::Page(page)) => target.map(|target| Resolved {
+                page: Some(page).filter(|_| target.platform == Platform::Generic),
+                target,
+            }),
+            Ok(Followed::Unresolved) => target.map(Resolved::from),
+            Err(e) => {
+                warn!("Failed to resolve share link {}: {}", input, e);
+                target.map(Resolved::from)
+            }
+        }
+    }
+
+    /// 逐跳跟随重定向，遇到能识别的直播间链接即停止，不下载跳转中的响应体，
+    /// 最后一跳的网页中读取 og:url
+    async fn follow(&self, mut url: Url) -> LsarResult<Followed> {
+        for _ in 0..MAX_REDIRECTS {
+            let response = self.client.get(url.as_str()).await?;
+            let status = response.status();
+
+            if status.is_redirection() {
+                let Some(location) = response
+                    .headers()
+                    .get(LOCATION)
+                    .and_then(|l| l.to_str().ok())
+                else {
+                    warn!("Redirect without location from {}", url);
+                    return Ok(Followed::Unresolved);
+                };
+
+                url = url.join(location)?;
+                debug!("Redirected to {}", url);
+                if let Some(target) = known_room(&url) {
+                    return Ok(Followed::Room(target));
+                }
+                continue;
+            }
+
+            if !status.is_success() {
+                debug!("Share link returned {}: {}", status, url);
+                return Ok(Followed::Unresolved);
+            }
+
+            let is_html = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|t| t.to_str().ok())
+                .is_some_and(|t| t.starts_with("text/html"));
+            if !is_html {
+                debug!("Share link is not a web page: {}", url);
+                return Ok(Followed::Unresolved);
+            }
+
+            let html = HttpClient::read_text_limited(response, MAX_PAGE_BYTES).await?;
+            let og_url = OG_URL_RE
+                .captures(&html)
+                .and_then(|c| url.join(&c[1].replace("&amp;", "&")).ok());
+            trace!("og:url of {}: {:?}", url, og_url);
+            return Ok(match og_url.and_then(|og_url| known_room(&og_url)) {
+                Some(target) => Followed::Room(target),
+                None => Followed::Page(FetchedPage { url, html }),
+            });
+        }
+
+        warn!("Too many redirects from share link");
+        Ok(Followed::Unresolved)
+    }
+}
+
+fn is_share_link(url: Option<&str>) -> bool {
+    url.and_then(|url| Url::parse(url).ok())
+        .and_then(|url| url.host_str().map(|h| SHARE_HOSTS.contains(&h)))
+        .unwrap_or(false)
+}
+
+/// 属于已支持平台且能得到房间的链接
+fn known_room(url: &Url) -> Option<RoomTarget> {
+    let host = url.host_str()?;
+    if SHARE_HOSTS.contains(&host) {
+        return None;
+    }
+
+    registry::site_for_host(host)?.room_from_url(url).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::parser::test_server::{Route, TestServer};
+
+    /// 录制的分享链接跳转过程，路径为本地服务中的路径
+    const CHAINS: &str = include_str!("../../tests/fixtures/share_links/chains.json");
+
+    #[tokio::test]
+    async fn test_recorded_chains() {
+        let chains: Vec<Value> = serde_json::from_str(CHAINS).unwrap();
+
+        for chain in chains {
+            let name = chain["name"].as_str().unwrap();
+            let routes = chain["responses"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| {
+                    let route = Route::get(
+                        r["path"].as_str().unwrap(),
+                        r["body"].as_str().unwrap_or(""),
+                    )
+                    .with_status(r["status"].as_u64().unwrap() as u16);
+                    let route = match r["contentType"].as_str() {
+                        Some(content_type) => route.with_header("Content-Type", content_type),
+                        None => route,
+                    };
+                    match r["location"].as_str() {
+                        Some(location) => route.with_header("Location", location),
+                        None => route,
+                    }
+                })
+                .collect();
+            let server = TestServer::start(routes).await;
+
+            let input = server.url(chain["input"].as_str().unwrap());
+            let target = resolve(&input).await.unwrap().target;
+
+            let expected = &chain["expected"];
+            assert_eq!(target.platform.to_str(), expected["platform"], "{}", name);
+            assert_eq!(
+                target.room_id,
+                expected["roomId"].as_str().unwrap_or(""),
+                "{}",
+                name
+            );
+            assert_eq!(target.url.as_deref(), expected["url"].as_str(), "{}", name);
+            assert_eq!(
+                server.requests().len(),
+                chain["responses"].as_array().unwrap().len(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_canonical_links_are_not_requested() {
+        let target = resolve("https://m.douyu.com/9999?dyshid=0-abc&dyshci=2")
+            .await
+            .unwrap()
+            .target;
+        assert_eq!(target, RoomTarget::new(Platform::Douyu, 9999));
+    }
+
+    #[tokio::test]
+    async fn test_unresolved_link_falls_back() {
+        let server = TestServer::start(vec![
+            Route::get("/page", "<html></html>").with_header("Content-Type", "text/html")
+        ])
+        .await;
+        let resolved = resolve(&server.url("/page")).await.unwrap();
+        assert_eq!(resolved.target.platform, Platform::Generic);
+
+        // 网页交给通用解析器，不再重复请求
+        let page = resolved.page.unwrap();
+        assert_eq!(page.url.as_str(), server.url("/page"));
+        assert_eq!(page.html, "<html></html>");
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_media_urls_are_not_requested() {
+        let server = TestServer::start(vec![
+            Route::get("/live/stream.flv", "FLV").with_header("Content-Type", "video/x-flv")
+        ])
+        .await;
+        let input = server.url("/live/stream.flv");
+
+        let resolved = resolve(&input).await.unwrap();
+        assert_eq!(resolved.target.platform, Platform::Generic);
+        assert_eq!(resolved.target.room_id, input);
+        assert!(resolved.page.is_none());
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_non_html_body_is_not_read() {
+        let page = r#"<meta property="og:url" content="https://www.huya.com/660000">"#;
+        let server =
+            TestServer::start(vec![Route::get("/download", page)
+                .with_header("Content-Type", "application/octet-stream")])
+            .await;
+
+        let resolved = resolve(&server.url("/download")).await.unwrap();
+        assert_eq!(resolved.target.platform, Platform::Generic);
+        assert!(resolved.page.is_none());
+    }
+}
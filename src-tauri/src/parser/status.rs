@@ -0,0 +1,114 @@
+//! 批量查询房间的直播状态，只请求各平台最轻量的接口，不获取直播流
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use super::registry::{self, LiveStatus, ParseContext, Platform};
+use super::room::RoomTarget;
+use crate::config::read_config_file;
+use crate::error::LsarResult;
+use crate::eval::EvalChannel;
+
+/// 同时进行的查询数量
+const MAX_CONCURRENT_CHECKS: usize = 4;
+
+#[derive(Debug, Deserialize)]
+pub struct RoomQuery {
+    platform: Platform,
+    #[serde(rename = "roomID")]
+    room_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoomLiveStatus {
+    platform: Platform,
+    #[serde(rename = "roomID")]
+    room_id: String,
+    #[serde(flatten)]
+    status: LiveStatus,
+    /// 查询失败的原因，失败时其他状态字段为默认值
+    error: Option<String>,
+}
+
+/// 平台是否有查询直播状态的轻量接口
+pub(crate) fn supports_live_status(platform: Platform) -> bool {
+    registry::site(platform).capabilities().live_status
+}
+
+/// 查询房间是否在直播，`room_id` 可以是支持房间名的平台的房间名
+pub(crate) async fn room_status(
+    platform: Platform,
+    room_id: &str,
+    ctx: &ParseContext,
+) -> LsarResult<LiveStatus> {
+    let target = RoomTarget::from_room(platform, room_id)?;
+    registry::site(platform).status(target, ctx).await
+}
+
+/// 结果与传入的房间顺序一致，单个房间查询失败不影响其他房间
+#[tauri::command]
+pub async fn check_live_status(
+    rooms: Vec<RoomQuery>,
+    eval_channel: tauri::State<'_, EvalChannel>,
+    app_handle: tauri::AppHandle,
+) -> LsarResult<Vec<RoomLiveStatus>> {
+    info!("Checking live status of {} rooms", rooms.len());
+
+    let ctx = Arc::new(ParseContext {
+        config: read_config_file().await?,
+        options: Default::default(),
+        eval_channel: eval_channel.inner().clone(),
+        app_handle,
+    });
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_CHECKS));
+
+    let tasks: Vec<_> = rooms
+        .into_iter()
+        .map(|room| {
+            let ctx = ctx.clone();
+            let semaphore = semaphore.clone();
+            let (platform, room_id) = (room.platform, room.room_id.clone());
+            let task = tokio::spawn(async move {
+                // 信号量不会被关闭
+                let _permit = semaphore.acquire_owned().await.unwrap();
+                room_status(platform, &room_id, &ctx).await
+            });
+            (room, task)
+        })
+        .collect();
+
+    // 按传入顺序等待各房间的任务，任务异常结束时也能对应到房间
+    let mut results = Vec::with_capacity(tasks.len());
+    for (room, task) in tasks {
+        let result = match task.await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(e) => {
+                error!("Live status check task failed: {}", e);
+                Err(e.to_string())
+            }
+        };
+
+        let (status, error) = match result {
+            Ok(status) => (status, None),
+            Err(e) => {
+                warn!(
+                    "Failed to check live status of {} room {}: {}",
+                    room.platform.to_str(),
+                    room.room_id,
+                    e
+                );
+                (LiveStatus::default(), Some(e))
+            }
+        };
+        results.push(RoomLiveStatus {
+            platform: room.platform,
+            room_id: room.room_id,
+            status,
+            error,
+        });
+    }
+
+    Ok(results)
+}
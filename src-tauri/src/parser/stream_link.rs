@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// 解析器无法得知清晰度时使用的名称
+pub(crate) const DEFAULT_QUALITY: &str = "默认";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Protocol {
+    Flv,
+    Hls,
+    Fmp4,
+    Dash,
+}
+
+impl Protocol {
+    /// 根据链接路径的扩展名推断协议，无法推断时返回 `None`
+    pub(crate) fn from_url(url: &str) -> Option<Self> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let ext = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase())?;
+
+        match ext.as_str() {
+            "flv" => Some(Protocol::Flv),
+            "m3u8" => Some(Protocol::Hls),
+            "mp4" | "m4s" => Some(Protocol::Fmp4),
+            "mpd" => Some(Protocol::Dash),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Codec {
+    Avc,
+    Hevc,
+    Av1,
+}
+
+impl Codec {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "avc" | "h264" | "264" => Some(Codec::Avc),
+            "hevc" | "h265" | "265" => Some(Codec::Hevc),
+            "av1" => Some(Codec::Av1),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamLink {
+    url: String,
+    protocol: Protocol,
+    /// 清晰度名称，如“原画”“蓝光”
+    quality: String,
+    /// 清晰度等级，越大越清晰，只在同一平台内可比较
+    level: u32,
+    codec: Option<Codec>,
+    /// 视频码率，单位 kbps
+    bitrate: Option<u32>,
+    /// CDN 名称，平台未提供时为链接的域名
+    cdn: Option<String>,
+    /// 平台标记的主线路
+    master: bool,
+    /// 链接的过期时间，unix 时间戳（秒）
+    expires_at: Option<i64>,
+    /// 播放此链接时需要携带的请求头
+    headers: BTreeMap<String, String>,
+}
+
+impl StreamLink {
+    pub(crate) fn new(url: String, protocol: Protocol) -> Self {
+        let parsed = Url::parse(&url).ok();
+        let cdn = parsed
+            .as_ref()
+            .and_then(|u| u.host_str())
+            .map(ToOwned::to_owned);
+        let expires_at = parsed.as_ref().and_then(expiry_from_query);
+
+        StreamLink {
+            url,
+            protocol,
+            quality: DEFAULT_QUALITY.to_owned(),
+            level: 0,
+            codec: None,
+            bitrate: None,
+            cdn,
+            master: false,
+            expires_at,
+            headers: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn with_quality<S: Into<String>>(mut self, quality: S, level: u32) -> Self {
+        self.quality = quality.into();
+        self.level = level;
+        self
+    }
+
+    pub(crate) fn with_codec(mut self, codec: Option<Codec>) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    pub(crate) fn with_bitrate(mut self, bitrate: u32) -> Self {
+        self.bitrate = Some(bitrate).filter(|b| *b > 0);
+        self
+    }
+
+    pub(crate) fn with_cdn<S: Into<String>>(mut self, cdn: S) -> Self {
+        self.cdn = Some(cdn.into());
+        self
+    }
+
+    pub(crate) fn with_master(mut self) -> Self {
+        self.master = true;
+        self
+    }
+
+    pub(crate) fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_owned(), value.to_owned());
+        self
+    }
+}
+
+/// 平台提供的可选清晰度，`value` 为重新解析时传给平台的参数
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct QualityOption {
+    pub(crate) value: u32,
+    pub(crate) label: String,
+    /// 未登录时不可用
+    #[serde(rename(serialize = "loginRequired"))]
+    pub(crate) login_required: bool,
+}
+
+/// 平台提供的可选 CDN，`value` 为重新解析时传给平台的参数
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CdnOption {
+    pub(crate) value: String,
+    pub(crate) label: String,
+}
+
+/// 各平台的防盗链参数中带有过期时间：`expires`、`expire` 为十进制，`wsTime` 为十六进制
+fn expiry_from_query(url: &Url) -> Option<i64> {
+    url.query_pairs().find_map(|(k, v)| match k.as_ref() {
+        "expires" | "expire" => v.parse().ok(),
+        "wsTime" => i64::from_str_radix(&v, 16).ok(),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_from_url() {
+        assert_eq!(
+            Protocol::from_url("https://a.com/live/1.flv?wsTime=1"),
+            Some(Protocol::Flv)
+        );
+        assert_eq!(
+            Protocol::from_url("https://a.com/live/index.m3u8"),
+            Some(Protocol::Hls)
+        );
+        assert_eq!(
+            Protocol::from_url("https://a.com/live/1_bluray.m4s?a=b.flv"),
+            Some(Protocol::Fmp4)
+        );
+        assert_eq!(Protocol::from_url("https://a.com/live/1"), None);
+    }
+
+    #[test]
+    fn test_link_metadata_from_url() {
+        let link = StreamLink::new(
+            "https://al.flv.huya.com/src/1.flv?wsSecret=x&wsTime=66f2a8c0".to_owned(),
+            Protocol::Flv,
+        );
+        assert_eq!(link.cdn.as_deref(), Some("al.flv.huya.com"));
+        assert_eq!(link.expires_at, Some(0x66f2a8c0));
+        assert_eq!(link.quality, DEFAULT_QUALITY);
+
+        let link = StreamLink::new(
+            "https://cn-gd.bilivideo.com/live-bvc/1.flv?expires=1727180000&len=0".to_owned(),
+            Protocol::Flv,
+        )
+        .with_quality("原画", 10000)
+        .with_cdn("cn-gd");
+        assert_eq!(link.expires_at, Some(1727180000));
+        assert_eq!(link.cdn.as_deref(), Some("cn-gd"));
+        assert_eq!(link.level, 10000);
+    }
+}
@@ -0,0 +1,203 @@
+//! 测试用的本地 HTTP 服务，按路径返回预设的响应并记录收到的请求
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[derive(Debug, Clone)]
+pub(crate) struct Route {
+    method: &'static str,
+    path: String,
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+    /// 请求体需要包含的内容，用于区分同一路径的不同请求
+    body_contains: Option<&'static str>,
+}
+
+impl Route {
+    pub(crate) fn get(path: impl Into<String>, body: impl Into<String>) -> Self {
+        Self::new("GET", path, body)
+    }
+
+    pub(crate) fn post(path: impl Into<String>, body: impl Into<String>) -> Self {
+        Self::new("POST", path, body)
+    }
+
+    pub(crate) fn head(path: impl Into<String>) -> Self {
+        Self::new("HEAD", path, "")
+    }
+
+    fn new(method: &'static str, path: impl Into<String>, body: impl Into<String>) -> Self {
+        Route {
+            method,
+            path: path.into(),
+            status: 200,
+            headers: Vec::new(),
+            body: body.into(),
+            body_contains: None,
+        }
+    }
+
+    pub(crate) fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub(crate) fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub(crate) fn when_body_contains(mut self, needle: &'static str) -> Self {
+        self.body_contains = Some(needle);
+        self
+    }
+
+    fn matches(&self, request: &Request) -> bool {
+        let path = request.target.split('?').next().unwrap_or_default();
+        self.method == request.method
+            && self.path == path
+            && self
+                .body_contains
+                .is_none_or(|needle| request.body.contains(needle))
+    }
+}
+
+/// 服务收到的请求
+#[derive(Debug, Clone)]
+pub(crate) struct Request {
+    pub(crate) method: String,
+    /// 包含查询参数的路径
+    pub(crate) target: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: String,
+}
+
+impl Request {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+pub(crate) struct TestServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl TestServer {
+    /// 在随机端口启动服务，未登记的路径返回 404
+    pub(crate) async fn start(routes: Vec<Route>) -> Self {
+        Self::start_with(|_| routes).await
+    }
+
+    /// 响应内容需要包含服务自身地址时使用，参数为 `127.0.0.1:端口`
+    pub(crate) async fn start_with(routes: impl FnOnce(&str) -> Vec<Route>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let routes = routes(&addr.to_string());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = requests.clone();
+        let routes = Arc::new(routes);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle(stream, routes.clone(), recorded.clone()));
+            }
+        });
+
+        TestServer { addr, requests }
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    pub(crate) fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn handle(
+    mut stream: TcpStream,
+    routes: Arc<Vec<Route>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+) {
+    let Some(request) = read_request(&mut stream).await else {
+        return;
+    };
+
+    let route = routes.iter().find(|r| r.matches(&request));
+    requests.lock().unwrap().push(request);
+
+    let response = match route {
+        Some(route) => {
+            let mut head = format!(
+                "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nConnection: close\r\n",
+                route.status,
+                route.body.len()
+            );
+            for (name, value) in &route.headers {
+                head.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            format!("{}\r\n{}", head, route.body)
+        }
+        None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".into(),
+    };
+
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 4096];
+
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_owned();
+    let target = request_line.next()?.to_owned();
+
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_owned(), v.trim().to_owned()))
+        .collect();
+
+    let length = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.parse().ok())
+        .unwrap_or(0);
+    while buf.len() < header_end + length {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let body = String::from_utf8_lossy(&buf[header_end..]).into_owned();
+
+    Some(Request {
+        method,
+        target,
+        headers,
+        body,
+    })
+}
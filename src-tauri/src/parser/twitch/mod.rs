@@ -0,0 +1,370 @@
+mod models;
+
+use async_trait::async_trait;
+use reqwest::header::HeaderName;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::error::{InputError, LsarError, LsarResult, RoomStateError};
+use crate::parser::hls::{parse_master_playlist, Variant};
+use crate::parser::registry::{Capabilities, ParseContext, Parser, Site};
+use crate::parser::room::{path_segments, RoomTarget};
+use crate::parser::ParsedResult;
+use crate::platform::Platform;
+
+use self::models::{GqlResponse, PlaybackAccessToken, TokenData, User, UserData};
+
+use super::http_client::HttpClient;
+
+const BASE_URL: &str = "https://www.twitch.tv/";
+const GQL_URL: &str = "https://gql.twitch.tv/gql";
+const USHER_URL: &str = "https://usher.ttvnw.net";
+/// 网页播放器使用的公开 Client-ID
+const CLIENT_ID: &str = "kimne78kx3ncx6brgo4mv6wki5h1ko";
+
+const USER_QUERY: &str = "query($login: String!) { user(login: $login) { login displayName broadcastSettings { title game { displayName } } stream { id type } } }";
+const TOKEN_QUERY: &str = "query($login: String!, $playerType: String!) { streamPlaybackAccessToken(channelName: $login, params: {platform: \"web\", playerBackend: \"mediaplayer\", playerType: $playerType}) { value signature } }";
+
+/// 非用户名的路径，如 https://www.twitch.tv/directory
+const RESERVED_PATHS: &[&str] = &[
+    "directory",
+    "downloads",
+    "jobs",
+    "p",
+    "search",
+    "settings",
+    "subscriptions",
+    "turbo",
+    "videos",
+    "wallet",
+];
+
+pub struct TwitchParser {
+    login: String,
+    gql_url: String,
+    usher_url: String,
+    client: HttpClient,
+}
+
+impl TwitchParser {
+    pub fn new(login: String) -> Self {
+        Self::with_endpoints(login, GQL_URL.to_owned(), USHER_URL.to_owned())
+    }
+
+    fn with_endpoints(login: String, gql_url: String, usher_url: String) -> Self {
+        let mut client = HttpClient::new();
+        client
+            .insert_header(HeaderName::from_static("client-id"), CLIENT_ID)
+            .unwrap();
+
+        TwitchParser {
+            login: login.to_lowercase(),
+            gql_url,
+            usher_url,
+            client,
+        }
+    }
+
+    async fn gql<T: DeserializeOwned>(&self, query: &str, variables: Value) -> LsarResult<T> {
+        let body = json!({ "query": query, "variables": variables });
+        let response: GqlResponse<T> =
+            self.client
+                .post_json(&self.gql_url, &body)
+                .await
+                .map_err(|e| {
+                    error!("Failed to send GQL request: {}", e);
+                    e
+                })?;
+
+        if let Some(err) = response.errors.first() {
+            error!("GQL request returned error: {}", err.message);
+            return Err(LsarError::Other(format!(
+                "Twitch 接口错误：{}",
+                err.message
+            )));
+        }
+
+        response.data.ok_or_else(|| {
+            error!("GQL response has no data");
+            LsarError::Other("Twitch 接口未返回数据".to_owned())
+        })
+    }
+
+    async fn get_user(&self) -> LsarResult<User> {
+        let data: UserData = self.gql(USER_QUERY, json!({ "login": self.login })).await?;
+
+        let user = data.user.ok_or_else(|| {
+            info!("User does not exist: {}", self.login);
+            RoomStateError::NotExists
+        })?;
+        debug!("Found user: {} ({})", user.display_name, user.login);
+        Ok(user)
+    }
+
+    async fn get_access_token(&self) -> LsarResult<PlaybackAccessToken> {
+        let data: TokenData = self
+            .gql(
+                TOKEN_QUERY,
+                json!({ "login": self.login, "playerType": "site" }),
+            )
+            .await?;
+
+        data.stream_playback_access_token.ok_or_else(|| {
+            warn!("No playback access token for: {}", self.login);
+            RoomStateError::NotExists.into()
+        })
+    }
+
+    fn master_playlist_url(&self, token: &PlaybackAccessToken) -> LsarResult<Url> {
+        let mut url = Url::parse(&format!(
+            "{}/api/channel/hls/{}.m3u8",
+            self.usher_url, self.login
+        ))?;
+        url.query_pairs_mut()
+            .append_pair("allow_source", "true")
+            .append_pair("allow_audio_only", "true")
+            .append_pair("fast_bread", "true")
+            .append_pair("player_backend", "mediaplayer")
+            .append_pair("playlist_include_framerate", "true")
+            .append_pair("p", &(rand::random::<u32>() % 1_000_000).to_string())
+            .append_pair("sig", &token.signature)
+            .append_pair("token", &token.value);
+        Ok(url)
+    }
+
+    async fn get_variants(&self, token: &PlaybackAccessToken) -> LsarResult<Vec<Variant>> {
+        let url = self.master_playlist_url(token)?;
+        let response = self.client.get(url.as_str()).await?;
+
+        let status = response.status();
+        if status.as_u16() == 404 {
+            info!("Usher returned 404, channel is offline: {}", self.login);
+            return Err(RoomStateError::Offline.into());
+        }
+        if !status.is_success() {
+            error!("Usher returned unexpected status: {}", status);
+            return Err(LsarError::Other(format!(
+                "获取 Twitch 播放列表失败：{}",
+                status
+            )));
+        }
+
+        let playlist = response.text().await.map_err(|e| {
+            error!("Failed to read master playlist: {}", e);
+            LsarError::from(e)
+        })?;
+        Ok(parse_master_playlist(&playlist, &url))
+    }
+
+    fn build_result(user: User, variants: Vec<Variant>) -> ParsedResult {
+        let (title, category) = match user.broadcast_settings {
+            Some(settings) => (
+                settings.title,
+                settings
+                    .game
+                    .map(|game| game.display_name)
+                    .unwrap_or_default(),
+            ),
+            None => (String::new(), String::new()),
+        };
+
+        ParsedResult {
+            platform: Platform::Twitch,
+            title,
+            anchor: user.display_name,
+            room_id: user.login,
+            category,
+            links: variants
+                .into_iter()
+                .map(Variant::into_stream_link)
+                .collect(),
+            qualities: Vec::new(),
+            current_quality: None,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Parser for TwitchParser {
+    async fn parse(&mut self) -> LsarResult<ParsedResult> {
+        info!("Starting Twitch parsing process: {}", self.login);
+
+        let user = self.get_user().await?;
+        let Some(stream) = &user.stream else {
+            info!("Channel is offline: {}", self.login);
+            return Err(RoomStateError::Offline.into());
+        };
+        debug!("Stream {} is {}", stream.id, stream.kind);
+
+        let token = self.get_access_token().await?;
+        let variants = self.get_variants(&token).await?;
+        if variants.is_empty() {
+            warn!("Master playlist has no variants");
+            return Err(RoomStateError::Offline.into());
+        }
+
+        let result = Self::build_result(user, variants);
+        info!("Twitch parsing process completed");
+        Ok(result)
+    }
+}
+
+pub(crate) struct TwitchSite;
+
+impl Site for TwitchSite {
+    fn platform(&self) -> Platform {
+        Platform::Twitch
+    }
+
+    fn label(&self) -> &'static str {
+        "Twitch"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        BASE_URL
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["twitch.tv"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            room_name: true,
+            ..Default::default()
+        }
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        match path_segments(url).first() {
+            Some(login) if !RESERVED_PATHS.contains(login) => {
+                Ok(RoomTarget::new(Platform::Twitch, login.to_lowercase()))
+            }
+            _ => Err(InputError::InvalidRoomId.into()),
+        }
+    }
+
+    fn parser(&self, target: RoomTarget, _ctx: &ParseContext) -> Box<dyn Parser> {
+        Box::new(TwitchParser::new(target.room_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::test_server::{Route, TestServer};
+
+    const USER: &str = include_str!("../../../tests/fixtures/twitch/user.json");
+    const USER_OFFLINE: &str = include_str!("../../../tests/fixtures/twitch/user_offline.json");
+    const TOKEN: &str = include_str!("../../../tests/fixtures/twitch/token.json");
+    const MASTER: &str = include_str!("../../../tests/fixtures/twitch/master.m3u8");
+
+    fn live_routes() -> Vec<Route> {
+        vec![
+            Route::post("/gql", USER).when_body_contains("user(login"),
+            Route::post("/gql", TOKEN).when_body_contains("streamPlaybackAccessToken"),
+            Route::get("/api/channel/hls/teststreamer.m3u8", MASTER),
+        ]
+    }
+
+    async fn parse_with(routes: Vec<Route>) -> (LsarResult<ParsedResult>, TestServer) {
+        let server = TestServer::start(routes).await;
+        let mut parser = TwitchParser::with_endpoints(
+            "TestStreamer".to_owned(),
+            server.url("/gql"),
+            server.url(""),
+        );
+        let result = parser.parse().await;
+        (result, server)
+    }
+
+    #[tokio::test]
+    async fn test_parse_live_channel() {
+        let (result, server) = parse_with(live_routes()).await;
+        let result = result.unwrap();
+
+        assert_eq!(result.platform, Platform::Twitch);
+        assert_eq!(result.room_id, "teststreamer");
+        assert_eq!(result.anchor, "TestStreamer");
+        assert_eq!(result.title, "Ranked grind all day");
+        assert_eq!(result.category, "VALORANT");
+
+        let qualities: Vec<_> = result
+            .links
+            .iter()
+            .map(|link| serde_json::to_value(link).unwrap()["quality"].clone())
+            .collect();
+        assert_eq!(
+            qualities,
+            [
+                "1080p60 (source)",
+                "720p60",
+                "480p",
+                "360p",
+                "160p",
+                "audio_only"
+            ]
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].header("client-id"), Some(CLIENT_ID));
+        let usher = Url::parse(&server.url(&requests[2].target)).unwrap();
+        let query: Vec<_> = usher.query_pairs().collect();
+        assert!(query.contains(&("sig".into(), "0123456789abcdef".into())));
+        assert!(query
+            .iter()
+            .any(|(k, v)| k == "token" && v.contains("\"channel\":\"teststreamer\"")));
+    }
+
+    #[tokio::test]
+    async fn test_parse_offline_channel() {
+        let (result, server) = parse_with(vec![Route::post("/gql", USER_OFFLINE)]).await;
+        assert!(matches!(
+            result,
+            Err(LsarError::RoomState(RoomStateError::Offline))
+        ));
+        assert_eq!(server.requests().len(), 1);
+
+        // 查询时仍在直播，获取播放列表时已下播
+        let mut routes = live_routes();
+        routes.pop();
+        routes.push(Route::get("/api/channel/hls/teststreamer.m3u8", "").with_status(404));
+        let (result, _) = parse_with(routes).await;
+        assert!(matches!(
+            result,
+            Err(LsarError::RoomState(RoomStateError::Offline))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_missing_user() {
+        let (result, _) = parse_with(vec![Route::post("/gql", r#"{"data":{"user":null}}"#)]).await;
+        assert!(matches!(
+            result,
+            Err(LsarError::RoomState(RoomStateError::NotExists))
+        ));
+    }
+
+    #[test]
+    fn test_room_from_input() {
+        let expected = RoomTarget::new(Platform::Twitch, "teststreamer");
+        assert_eq!(
+            RoomTarget::from_input("https://www.twitch.tv/TestStreamer").unwrap(),
+            expected
+        );
+        assert_eq!(
+            RoomTarget::from_input("https://m.twitch.tv/teststreamer/videos").unwrap(),
+            expected
+        );
+        assert_eq!(
+            RoomTarget::from_input("twitch:TestStreamer").unwrap(),
+            expected
+        );
+        assert!(RoomTarget::from_input("https://www.twitch.tv/directory").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GqlResponse<T> {
+    pub(super) data: Option<T>,
+    #[serde(default)]
+    pub(super) errors: Vec<GqlError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct GqlError {
+    pub(super) message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UserData {
+    /// 用户不存在时为 null
+    pub(super) user: Option<User>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct User {
+    pub(super) login: String,
+    pub(super) display_name: String,
+    pub(super) broadcast_settings: Option<BroadcastSettings>,
+    /// 未开播时为 null
+    pub(super) stream: Option<Stream>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct BroadcastSettings {
+    #[serde(default)]
+    pub(super) title: String,
+    pub(super) game: Option<Game>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct Game {
+    pub(super) display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct Stream {
+    pub(super) id: String,
+    /// live 或 rerun
+    #[serde(default, rename = "type")]
+    pub(super) kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct TokenData {
+    pub(super) stream_playback_access_token: Option<PlaybackAccessToken>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct PlaybackAccessToken {
+    /// json 字符串，原样作为 usher 的 token 参数
+    pub(super) value: String,
+    pub(super) signature: String,
+}
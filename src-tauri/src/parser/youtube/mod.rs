@@ -0,0 +1,343 @@
+mod models;
+
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT_LANGUAGE, COOKIE};
+use url::Url;
+
+use crate::error::{InputError, LsarError, LsarResult, MissKeyFieldError, RoomStateError};
+use crate::parser::hls::{parse_master_playlist, Variant};
+use crate::parser::registry::{Capabilities, ParseContext, Parser, Site};
+use crate::parser::room::{path_segments, RoomTarget};
+use crate::parser::stream_link::{Protocol, StreamLink};
+use crate::parser::ParsedResult;
+use crate::platform::Platform;
+
+use self::models::PlayerResponse;
+
+use super::http_client::HttpClient;
+
+const BASE_URL: &str = "https://www.youtube.com/watch?v=";
+const CHANNEL_BASE_URL: &str = "https://www.youtube.com/";
+
+/// 观看页中正在直播的视频
+#[derive(Debug)]
+struct LiveStream {
+    video_id: String,
+    title: String,
+    author: String,
+    category: String,
+    manifest_url: String,
+}
+
+pub struct YouTubeParser {
+    page_url: String,
+    client: HttpClient,
+}
+
+impl YouTubeParser {
+    pub fn new(page_url: String) -> Self {
+        let mut client = HttpClient::new();
+        // 跳过欧盟地区的 cookie 同意页
+        client.insert_header(COOKIE, "SOCS=CAI").unwrap();
+        client
+            .insert_header(ACCEPT_LANGUAGE, "en-US,en;q=0.9")
+            .unwrap();
+
+        YouTubeParser { page_url, client }
+    }
+
+    /// 频道未开播时 `/@handle/live` 是频道主页，没有 `ytInitialPlayerResponse`
+    fn extract_player_response(html: &str) -> LsarResult<Option<PlayerResponse>> {
+        trace!("Extracting ytInitialPlayerResponse from HTML");
+        let Some(json) = html
+            .split("ytInitialPlayerResponse = ")
+            .skip(1)
+            .map(str::trim_start)
+            .find(|s| s.starts_with('{'))
+        else {
+            return Ok(None);
+        };
+
+        // 只解析第一个 json 对象，忽略之后的脚本
+        let response = serde_json::Deserializer::from_str(json)
+            .into_iter::<PlayerResponse>()
+            .next()
+            .ok_or(MissKeyFieldError::PageState)?
+            .map_err(|e| {
+                error!("Failed to parse ytInitialPlayerResponse: {}", e);
+                e
+            })?;
+
+        debug!("Playability status: {:?}", response.playability_status);
+        Ok(Some(response))
+    }
+
+    fn parse_page(html: &str) -> LsarResult<LiveStream> {
+        let Some(response) = Self::extract_player_response(html)? else {
+            info!("No player response in page, the channel is not live");
+            return Err(RoomStateError::Offline.into());
+        };
+
+        let status = &response.playability_status;
+        if status.status == "ERROR" {
+            info!("Video is unavailable: {}", status.reason);
+            return Err(RoomStateError::NotExists.into());
+        }
+        let Some(details) = response.video_details else {
+            warn!("Player response has no video details");
+            return Err(RoomStateError::NotExists.into());
+        };
+
+        let microformat = response.microformat.map(|m| m.player_microformat_renderer);
+        let broadcast = microformat
+            .as_ref()
+            .and_then(|m| m.live_broadcast_details.as_ref());
+
+        if details.is_upcoming || status.status == "LIVE_STREAM_OFFLINE" {
+            info!("Broadcast is upcoming: {}", details.video_id);
+            return Err(RoomStateError::Upcoming.into());
+        }
+        if !details.is_live_content && broadcast.is_none() {
+            info!("Video is not a live broadcast: {}", details.video_id);
+            return Err(RoomStateError::NotLive.into());
+        }
+        let is_live = details.is_live || broadcast.is_some_and(|b| b.is_live_now);
+        if !is_live || broadcast.is_some_and(|b| b.end_timestamp.is_some()) {
+            info!("Broadcast has ended: {}", details.video_id);
+            return Err(RoomStateError::Ended.into());
+        }
+
+        if status.status != "OK" {
+            // 会员专享、年龄限制等
+            warn!(
+                "Live stream is not playable: {} {}",
+                status.status, status.reason
+            );
+            return Err(LsarError::Other(format!(
+                "无法播放该直播：{}",
+                status.reason
+            )));
+        }
+
+        let manifest_url = response
+            .streaming_data
+            .and_then(|data| data.hls_manifest_url)
+            .ok_or_else(|| {
+                error!("Live stream has no HLS manifest: {}", details.video_id);
+                RoomStateError::Offline
+            })?;
+
+        Ok(LiveStream {
+            video_id: details.video_id,
+            title: details.title,
+            author: details.author,
+            category: microformat.map(|m| m.category).unwrap_or_default(),
+            manifest_url,
+        })
+    }
+
+    async fn get_variants(&self, manifest_url: &str) -> LsarResult<Vec<Variant>> {
+        let url = Url::parse(manifest_url)?;
+        let playlist = self.client.get_text(manifest_url).await.map_err(|e| {
+            error!("Failed to fetch HLS manifest: {}", e);
+            e
+        })?;
+        Ok(parse_master_playlist(&playlist, &url))
+    }
+
+    /// 主播放列表在前，之后是各清晰度的播放列表
+    fn build_result(live: LiveStream, variants: Vec<Variant>) -> ParsedResult {
+        let links = std::iter::once(StreamLink::new(live.manifest_url, Protocol::Hls))
+            .chain(variants.into_iter().map(Variant::into_stream_link))
+            .collect();
+
+        ParsedResult {
+            platform: Platform::YouTube,
+            title: live.title,
+            anchor: live.author,
+            room_id: live.video_id,
+            category: live.category,
+            links,
+            qualities: Vec::new(),
+            current_quality: None,
+            cdns: Vec::new(),
+            current_cdn: None,
+            notices: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Parser for YouTubeParser {
+    async fn parse(&mut self) -> LsarResult<ParsedResult> {
+        info!("Starting YouTube parsing process: {}", self.page_url);
+
+        let html = self.client.get_text(&self.page_url).await.map_err(|e| {
+            error!("Failed to fetch watch page: {}", e);
+            e
+        })?;
+        let live = Self::parse_page(&html)?;
+        let variants = self.get_variants(&live.manifest_url).await?;
+
+        let result = Self::build_result(live, variants);
+        info!("YouTube parsing process completed");
+        Ok(result)
+    }
+}
+
+pub(crate) struct YouTubeSite;
+
+impl Site for YouTubeSite {
+    fn platform(&self) -> Platform {
+        Platform::YouTube
+    }
+
+    fn label(&self) -> &'static str {
+        "YouTube"
+    }
+
+    fn room_base_url(&self) -> &'static str {
+        BASE_URL
+    }
+
+    fn url_patterns(&self) -> &'static [&'static str] {
+        &["youtube.com", "youtu.be"]
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            room_name: true,
+            ..Default::default()
+        }
+    }
+
+    fn room_from_url(&self, url: &Url) -> LsarResult<RoomTarget> {
+        let segments = path_segments(url);
+
+        if url.host_str() == Some("youtu.be") {
+            return segments
+                .first()
+                .map(|id| RoomTarget::new(Platform::YouTube, id))
+                .ok_or_else(|| InputError::InvalidRoomId.into());
+        }
+
+        match segments.as_slice() {
+            ["watch"] => url
+                .query_pairs()
+                .find(|(k, _)| k == "v")
+                .map(|(_, id)| RoomTarget::new(Platform::YouTube, id))
+                .ok_or_else(|| InputError::InvalidRoomId.into()),
+            ["live", id] => Ok(RoomTarget::new(Platform::YouTube, id)),
+            // 频道的直播页会跳转到正在直播的视频
+            [handle, ..] if handle.starts_with('@') => {
+                let url = Url::parse(&format!("{}{}/live", CHANNEL_BASE_URL, handle))?;
+                Ok(RoomTarget::with_url(Platform::YouTube, &url))
+            }
+            ["channel", id, ..] => {
+                let url = Url::parse(&format!("{}channel/{}/live", CHANNEL_BASE_URL, id))?;
+                Ok(RoomTarget::with_url(Platform::YouTube, &url))
+            }
+            _ => Err(InputError::InvalidRoomId.into()),
+        }
+    }
+
+    fn parser(&self, target: RoomTarget, _ctx: &ParseContext) -> Box<dyn Parser> {
+        let page_url = target
+            .url
+            .unwrap_or_else(|| format!("{}{}", BASE_URL, target.room_id));
+        Box::new(YouTubeParser::new(page_url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WATCH_LIVE: &str = include_str!("../../../tests/fixtures/youtube/watch_live.html");
+    const WATCH_UPCOMING: &str =
+        include_str!("../../../tests/fixtures/youtube/watch_upcoming.html");
+    const WATCH_ENDED: &str = include_str!("../../../tests/fixtures/youtube/watch_ended.html");
+    const WATCH_VIDEO: &str = include_str!("../../../tests/fixtures/youtube/watch_video.html");
+    const CHANNEL_OFFLINE: &str =
+        include_str!("../../../tests/fixtures/youtube/channel_offline.html");
+    const MASTER: &str = include_str!("../../../tests/fixtures/youtube/master.m3u8");
+
+    fn room_state(html: &str) -> Option<RoomStateError> {
+        match YouTubeParser::parse_page(html) {
+            Err(LsarError::RoomState(state)) => Some(state),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_live_page() {
+        let live = YouTubeParser::parse_page(WATCH_LIVE).unwrap();
+        assert_eq!(live.video_id, "jfKfPfyJRdk");
+        assert_eq!(live.author, "Lofi Test Radio");
+        assert_eq!(live.category, "Music");
+        assert!(live
+            .manifest_url
+            .starts_with("https://manifest.googlevideo.com/api/manifest/hls_variant/"));
+
+        let url = Url::parse(&live.manifest_url).unwrap();
+        let result = YouTubeParser::build_result(live, parse_master_playlist(MASTER, &url));
+        assert_eq!(result.platform, Platform::YouTube);
+        assert_eq!(result.title, "lofi beats to relax/study to");
+
+        let qualities: Vec<_> = result
+            .links
+            .iter()
+            .map(|link| serde_json::to_value(link).unwrap()["quality"].clone())
+            .collect();
+        assert_eq!(
+            qualities,
+            ["默认", "1080p", "720p", "480p", "360p", "240p", "144p"]
+        );
+    }
+
+    #[test]
+    fn test_parse_not_live_pages() {
+        assert!(matches!(
+            room_state(WATCH_UPCOMING),
+            Some(RoomStateError::Upcoming)
+        ));
+        assert!(matches!(
+            room_state(WATCH_ENDED),
+            Some(RoomStateError::Ended)
+        ));
+        assert!(matches!(
+            room_state(WATCH_VIDEO),
+            Some(RoomStateError::NotLive)
+        ));
+        assert!(matches!(
+            room_state(CHANNEL_OFFLINE),
+            Some(RoomStateError::Offline)
+        ));
+    }
+
+    #[test]
+    fn test_room_from_input() {
+        let expected = RoomTarget::new(Platform::YouTube, "jfKfPfyJRdk");
+        for input in [
+            "https://www.youtube.com/watch?v=jfKfPfyJRdk",
+            "https://m.youtube.com/watch?v=jfKfPfyJRdk&feature=share",
+            "https://youtu.be/jfKfPfyJRdk?si=abc",
+            "https://www.youtube.com/live/jfKfPfyJRdk",
+            "youtube:jfKfPfyJRdk",
+        ] {
+            assert_eq!(
+                RoomTarget::from_input(input).unwrap(),
+                expected,
+                "{}",
+                input
+            );
+        }
+
+        let target = RoomTarget::from_input("https://www.youtube.com/@LofiGirl/live").unwrap();
+        assert_eq!(
+            target.url.as_deref(),
+            Some("https://www.youtube.com/@LofiGirl/live")
+        );
+        assert!(RoomTarget::from_input("https://www.youtube.com/feed/trending").is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+/// 观看页中 `ytInitialPlayerResponse` 的部分字段
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct PlayerResponse {
+    pub(super) playability_status: PlayabilityStatus,
+    pub(super) streaming_data: Option<StreamingData>,
+    pub(super) video_details: Option<VideoDetails>,
+    pub(super) microformat: Option<Microformat>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct PlayabilityStatus {
+    /// OK、LIVE_STREAM_OFFLINE、ERROR、LOGIN_REQUIRED、UNPLAYABLE 等
+    pub(super) status: String,
+    #[serde(default)]
+    pub(super) reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct StreamingData {
+    pub(super) hls_manifest_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct VideoDetails {
+    pub(super) video_id: String,
+    #[serde(default)]
+    pub(super) title: String,
+    #[serde(default)]
+    pub(super) author: String,
+    #[serde(default)]
+    pub(super) is_live: bool,
+    #[serde(default)]
+    pub(super) is_live_content: bool,
+    #[serde(default)]
+    pub(super) is_upcoming: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct Microformat {
+    pub(super) player_microformat_renderer: PlayerMicroformat,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct PlayerMicroformat {
+    #[serde(default)]
+    pub(super) category: String,
+    pub(super) live_broadcast_details: Option<LiveBroadcastDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all(deserialize = "camelCase"))]
+pub(super) struct LiveBroadcastDetails {
+    #[serde(default)]
+    pub(super) is_live_now: bool,
+    /// 直播结束后才有
+    pub(super) end_timestamp: Option<String>,
+}
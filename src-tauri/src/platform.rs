@@ -1,61 +1,50 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-pub(crate) enum Platform {
-    Douyu = 0,
-    Huya,
-    Douyin,
-    Bilibili,
-}
-
-impl Platform {
-    pub(crate) fn as_i64(&self) -> i64 {
-        match self {
-            Platform::Douyu => 0,
-            Platform::Huya => 1,
-            Platform::Douyin => 2,
-            Platform::Bilibili => 3,
+/// 由解析器注册表生成 `Platform` 枚举及其与字符串键之间的转换，前端和数据库都使用字符串键，
+/// 新增平台只需在 `parser::registry` 中登记一行。
+macro_rules! platforms {
+    ($($variant:ident => $key:literal : $site:expr),+ $(,)?) => {
+        #[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash)]
+        pub(crate) enum Platform {
+            $(
+                #[serde(rename = $key)]
+                $variant,
+            )+
         }
-    }
 
-    pub(crate) fn to_str(&self) -> &str {
-        self.into()
-    }
-}
+        impl Platform {
+            pub(crate) fn to_str(self) -> &'static str {
+                self.into()
+            }
+        }
 
-impl TryFrom<i64> for Platform {
-    type Error = &'static str;
+        impl TryFrom<&str> for Platform {
+            type Error = &'static str;
 
-    fn try_from(value: i64) -> Result<Self, Self::Error> {
-        match value {
-            0 => Ok(Platform::Douyu),
-            1 => Ok(Platform::Huya),
-            2 => Ok(Platform::Douyin),
-            3 => Ok(Platform::Bilibili),
-            _ => Err("Invalid platform value"),
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                match value {
+                    $($key => Ok(Platform::$variant),)+
+                    _ => Err("Invalid platform value"),
+                }
+            }
         }
-    }
-}
 
-impl From<Platform> for &'static str {
-    fn from(platform: Platform) -> Self {
-        match platform {
-            Platform::Douyu => "douyu",
-            Platform::Huya => "huya",
-            Platform::Douyin => "douyin",
-            Platform::Bilibili => "bilibili",
+        impl From<Platform> for &'static str {
+            fn from(platform: Platform) -> Self {
+                match platform {
+                    $(Platform::$variant => $key,)+
+                }
+            }
         }
-    }
-}
 
-impl From<&Platform> for &'static str {
-    fn from(platform: &Platform) -> Self {
-        match platform {
-            Platform::Douyu => "douyu",
-            Platform::Huya => "huya",
-            Platform::Douyin => "douyin",
-            Platform::Bilibili => "bilibili",
+        impl From<&Platform> for &'static str {
+            fn from(platform: &Platform) -> Self {
+                (*platform).into()
+            }
         }
-    }
+
+        pub(crate) static SITES: &[&dyn Site] = &[$(&$site),+];
+    };
 }
+
+pub(crate) use platforms;
+
+pub(crate) use crate::parser::Platform;
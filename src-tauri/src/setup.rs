@@ -1,12 +1,10 @@
-use std::sync::Arc;
-
 use tauri::Manager;
 use time::macros::{format_description, offset};
-use tokio::sync::Mutex;
 use tracing::Level;
 use tracing_subscriber::fmt::time::OffsetTime;
 
 use crate::eval::EvalChannel;
+use crate::monitor::spawn_monitor;
 
 pub fn setup_logging() {
     let fmt = if cfg!(debug_assertions) {
@@ -57,11 +55,10 @@ pub fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>>
     #[cfg(any(target_os = "macos", target_os = "windows"))]
     apply_window_effect(app)?;
 
-    let eval_channel = EvalChannel {
-        sender: Arc::new(Mutex::new(None)),
-    };
+    let eval_channel = EvalChannel::default();
+    app.manage(eval_channel.clone());
 
-    app.manage(eval_channel);
+    spawn_monitor(app.handle().clone(), eval_channel);
 
     info!("Application setup completed");
 
@@ -2,8 +2,13 @@ use md5::{Digest, Md5};
 
 #[tauri::command]
 pub async fn md5(text: String) -> String {
+    md5_hex(&text)
+}
+
+/// 供后端同步调用，如 js 引擎中的 `CryptoJS.MD5`
+pub(crate) fn md5_hex(text: &str) -> String {
     let mut hasher = Md5::new();
-    hasher.update(&text);
+    hasher.update(text);
     let result = hasher.finalize();
     // let bytes: &[u8] = &result[..];
     // debug!("md5 bytes: {:?}", bytes);
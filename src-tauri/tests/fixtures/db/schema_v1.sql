@@ -0,0 +1,17 @@
+-- 引入版本号之前的 lsar.db，只有播放历史表
+CREATE TABLE history (
+    id              INTEGER PRIMARY KEY,
+    platform        INTEGER NOT NULL,
+    room_id         INTEGER NOT NULL,
+    anchor          TEXT NOT NULL,
+    category        TEXT NOT NULL,
+    last_title      TEXT NOT NULL,
+    last_play_time  DATETIME NOT NULL
+);
+CREATE UNIQUE INDEX idx_unique_platform_room_id ON history (platform, room_id);
+
+INSERT INTO history (platform, room_id, anchor, category, last_title, last_play_time) VALUES
+    (0, 9999, '斗鱼测试主播', '户外', '深夜电台', '2024-09-01 20:00:00.0 +00:00:00'),
+    (3, 21452505, 'B站测试主播', '唱见电台', '周六点歌', '2024-09-02 21:30:00.0 +00:00:00'),
+    (1, 660000, '虎牙测试主播', '王者荣耀', '冲分', '2024-09-03 19:15:00.0 +00:00:00'),
+    (42, 123, '未知平台主播', '', '', '2024-09-04 18:00:00.0 +00:00:00');
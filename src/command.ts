@@ -46,7 +46,46 @@ export const insertHistory = async (history: HistoryItem) =>
 export const deleteHistoryByID = async (id: number) =>
   invoke<void>("delete_a_history_by_id", { id });
 
-export const evalResult = async (result: string) =>
-  invoke<void>("eval_result", { result });
+export const evalResult = async (id: number, response: EvalResponse) =>
+  invoke<void>("eval_result", { id, response });
+
+export const listFavorites = async () =>
+  invoke<FavoriteItem[]>("list_favorites");
+
+export const addFavorite = async (favorite: NewFavorite) =>
+  invoke<FavoriteItem>("add_favorite", { favorite });
+
+export const promoteHistoryToFavorite = async (historyId: number) =>
+  invoke<FavoriteItem>("promote_history_to_favorite", { historyId });
+
+export const removeFavorite = async (id: number) =>
+  invoke<void>("remove_favorite", { id });
+
+export const reorderFavorites = async (ids: number[]) =>
+  invoke<void>("reorder_favorites", { ids });
+
+export const setFavoriteTags = async (id: number, tags: string[]) =>
+  invoke<void>("set_favorite_tags", { id, tags });
+
+export const updateFavorite = async (id: number, changes: FavoriteChanges) =>
+  invoke<void>("update_favorite", { id, changes });
+
+export const parseURL = async (input: string, options?: ParseOptions) =>
+  invoke<ParsedResult>("parse_url", { input, options });
+
+export const listPlatforms = async () =>
+  invoke<PlatformInfo[]>("list_platforms");
+
+export const checkLiveStatus = async (rooms: RoomQuery[]) =>
+  invoke<RoomLiveStatus[]>("check_live_status", { rooms });
+
+export const getBilibiliLoginQrCode = async () =>
+  invoke<LoginQrCode>("get_bilibili_login_qrcode");
+
+export const pollBilibiliLogin = async (key: string) =>
+  invoke<LoginStatus>("poll_bilibili_login", { key });
+
+export const importBrowserCookies = async () =>
+  invoke<ImportedCookie[]>("import_browser_cookies");
 
 export const getPlayerPaths = async () => invoke<string[]>("get_player_paths");
@@ -1,6 +1,11 @@
-import { AiFillApi, AiFillChrome, AiFillDelete } from "solid-icons/ai";
+import {
+  AiFillApi,
+  AiFillChrome,
+  AiFillDelete,
+  AiFillStar,
+} from "solid-icons/ai";
 import { createSignal, Show } from "solid-js";
-import { deleteHistoryByID, open } from "~/command";
+import { deleteHistoryByID, open, promoteHistoryToFavorite } from "~/command";
 import { useAppContext } from "~/context";
 import {
   LazyButton,
@@ -25,9 +30,8 @@ const HistoryItem = (props: HistoryItemProps) => {
   const [
     _,
     { setToast },
-    { config },
+    ,
     { setParsedResult },
-    { setShowSettings: setShowBilibiliCookieEditor },
   ] = useAppContext();
 
   const [parsing, setParsing] = createSignal(false);
@@ -37,6 +41,15 @@ const HistoryItem = (props: HistoryItemProps) => {
     props.onDelete();
   };
 
+  const onFavorite = async () => {
+    try {
+      await promoteHistoryToFavorite(props.id);
+      setToast({ type: "success", message: `已收藏 ${props.anchor}` });
+    } catch (e) {
+      setToast({ type: "error", message: String(e) });
+    }
+  };
+
   const onParse = async () => {
     props.startParsing();
     setParsing(true);
@@ -44,8 +57,6 @@ const HistoryItem = (props: HistoryItemProps) => {
     await parse(
       props.platform,
       props.room_id,
-      config()!,
-      setShowBilibiliCookieEditor,
       setToast,
       setParsedResult,
     );
@@ -73,17 +84,17 @@ const HistoryItem = (props: HistoryItemProps) => {
       <LazyDivider />
 
       <LazyRow>
-        <LazyCol span={15} align="center">
+        <LazyCol span={13} align="center">
           <LazyText>{props.anchor}</LazyText>
         </LazyCol>
 
         <LazyCol span={3} align="center">
           <LazyText type="secondary">
-            {platforms[props.platform].label}
+            {platforms()[props.platform]?.label}
           </LazyText>
         </LazyCol>
 
-        <LazyCol span={6} align="center">
+        <LazyCol span={8} align="center">
           <LazySpace>
             <LazyTooltip
               text="解析本直播间"
@@ -113,11 +124,21 @@ const HistoryItem = (props: HistoryItemProps) => {
                 shape="circle"
                 size="small"
                 onClick={() =>
-                  open(platforms[props.platform].roomBaseURL + props.room_id)
+                  open(platforms()[props.platform]?.roomBaseURL + props.room_id)
                 }
               />
             </LazyTooltip>
 
+            <LazyTooltip text="收藏此直播间" placement="bottom" delay={1000}>
+              <LazyButton
+                icon={<AiFillStar />}
+                type="plain"
+                shape="circle"
+                size="small"
+                onClick={onFavorite}
+              />
+            </LazyTooltip>
+
             <LazyTooltip
               text="删除本条历史记录"
               placement="bottom"
@@ -1,9 +1,9 @@
 import { AiFillChrome, AiFillCopy, AiFillPlayCircle } from "solid-icons/ai";
-import { For, useContext } from "solid-js";
+import { For, Show, useContext } from "solid-js";
 import { insertHistory, open, play } from "~/command";
 import { AppContext } from "~/context";
 import { writeText } from "@tauri-apps/plugin-clipboard-manager";
-import { platforms } from "~/parser";
+import { parse, platforms } from "~/parser";
 import {
   LazyAlert,
   LazyButton,
@@ -13,6 +13,7 @@ import {
   LazyLabel,
   LazyRow,
   LazySpace,
+  LazyTag,
   LazyText,
   LazyTooltip,
 } from "~/lazy";
@@ -22,10 +23,24 @@ const Result = (props: ParsedResult) => {
   const [
     { refetchHistoryItems },
     { setToast },
-    _,
+    ,
     { parsedResult, setParsedResult },
   ] = useContext(AppContext)!;
 
+  // 切换清晰度或 CDN 时保留另一项的当前值
+  const reparse = (options: ParseOptions) =>
+    parse(
+      props.platform,
+      props.roomID,
+      setToast,
+      setParsedResult,
+      {
+        quality: props.currentQuality ?? undefined,
+        cdn: props.currentCDN ?? undefined,
+        ...options,
+      },
+    );
+
   const removeLink = (index: number) => {
     setParsedResult((prev) => ({
       ...prev!,
@@ -34,7 +49,7 @@ const Result = (props: ParsedResult) => {
   };
 
   const onPlay = async (index: number) => {
-    await play(props!.links[index]);
+    await play(props!.links[index].url);
 
     // 解析出来的链接只能访问一次，访问后即删除
     removeLink(index);
@@ -72,7 +87,7 @@ const Result = (props: ParsedResult) => {
               size="small"
               type="plain"
               onClick={() =>
-                open(platforms[props.platform].roomBaseURL + props.roomID)
+                open(platforms()[props.platform]?.roomBaseURL + props.roomID)
               }
             />
           </LazyTooltip>
@@ -90,6 +105,50 @@ const Result = (props: ParsedResult) => {
           </LazySpace>
         </LazySpace>
 
+        <Show when={props.qualities.length}>
+          <LazySpace gap={8}>
+            <LazyLabel>清晰度</LazyLabel>
+            <For each={props.qualities}>
+              {(item) => (
+                <LazyTag
+                  color={
+                    props.currentQuality === item.value
+                      ? "var(--alley-color-success)"
+                      : "default"
+                  }
+                  onClick={() => reparse({ quality: item.value })}
+                >
+                  {item.loginRequired ? `${item.label}（需登录）` : item.label}
+                </LazyTag>
+              )}
+            </For>
+          </LazySpace>
+        </Show>
+
+        <For each={props.notices}>
+          {(notice) => <LazyAlert type="warning" message={notice} showIcon />}
+        </For>
+
+        <Show when={props.cdns.length}>
+          <LazySpace gap={8}>
+            <LazyLabel>CDN</LazyLabel>
+            <For each={props.cdns}>
+              {(item) => (
+                <LazyTag
+                  color={
+                    props.currentCDN === item.value
+                      ? "var(--alley-color-success)"
+                      : "default"
+                  }
+                  onClick={() => reparse({ cdn: item.value })}
+                >
+                  {item.label}
+                </LazyTag>
+              )}
+            </For>
+          </LazySpace>
+        </Show>
+
         <LazyDivider
           dashed
           style={{ "--alley-color-split": "#fff", margin: "8px 0" }}
@@ -103,7 +162,16 @@ const Result = (props: ParsedResult) => {
               <LazyRow>
                 <LazyCol span={21} align="center">
                   <LazyText class="link" ellipsis={{ rows: 1 }}>
-                    {link}
+                    {`[${[
+                      link.quality,
+                      link.protocol.toUpperCase(),
+                      link.codec?.toUpperCase(),
+                      link.bitrate && `${link.bitrate}kbps`,
+                      link.cdn,
+                      link.master && "主线路",
+                    ]
+                      .filter(Boolean)
+                      .join(" ")}] ${link.url}`}
                   </LazyText>
                 </LazyCol>
 
@@ -128,7 +196,7 @@ const Result = (props: ParsedResult) => {
                       shape="circle"
                       size="small"
                       type="plain"
-                      onClick={() => onCopy(link)}
+                      onClick={() => onCopy(link.url)}
                     />
                   </LazyTooltip>
                 </LazyCol>
@@ -1,6 +1,6 @@
 import "./index.scss";
 import { children, createSignal, For } from "solid-js";
-import { parse, platforms } from "~/parser";
+import { parse, platformList } from "~/parser";
 import { AiOutlineCheck } from "solid-icons/ai";
 import { useAppContext } from "~/context";
 import {
@@ -17,9 +17,8 @@ const Search = () => {
   const [
     _,
     { setToast },
-    { config },
+    ,
     { setParsedResult },
-    { setShowSettings: setShowBilibiliCookieEditor },
   ] = useAppContext();
 
   const [input, setInput] = createSignal<string>("");
@@ -46,23 +45,19 @@ const Search = () => {
   };
 
   const buttons = children(() => (
-    <For each={Object.keys(platforms)}>
-      {(key) => {
-        const item = platforms[key as Platform];
-
-        return (
-          <LazyTag
-            color={
-              currentPlatform() === key
-                ? "var(--alley-color-success)"
-                : "default"
-            }
-            onClick={() => selectPlatform(key as Platform)}
-          >
-            {item.label}
-          </LazyTag>
-        );
-      }}
+    <For each={platformList()}>
+      {(item) => (
+        <LazyTag
+          color={
+            currentPlatform() === item.key
+              ? "var(--alley-color-success)"
+              : "default"
+          }
+          onClick={() => selectPlatform(item.key)}
+        >
+          {item.label}
+        </LazyTag>
+      )}
     </For>
   ));
 
@@ -79,8 +74,6 @@ const Search = () => {
     await parse(
       currentPlatform()!,
       parsedInput,
-      config()!,
-      setShowBilibiliCookieEditor,
       setToast,
       setParsedResult,
     );
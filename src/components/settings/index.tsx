@@ -1,7 +1,21 @@
 import { open } from "@tauri-apps/plugin-dialog";
-import { createEffect, createSignal, Show, useContext } from "solid-js";
-import { getPlayerPaths, writeConfigFile } from "~/command";
+import {
+  createEffect,
+  createSignal,
+  onCleanup,
+  Show,
+  useContext,
+} from "solid-js";
+import {
+  getBilibiliLoginQrCode,
+  getPlayerPaths,
+  importBrowserCookies,
+  pollBilibiliLogin,
+  readConfigFile,
+  writeConfigFile,
+} from "~/command";
 import { AppContext } from "~/context";
+import { platforms } from "~/parser";
 import {
   LazyButton,
   LazyDialog,
@@ -48,6 +62,91 @@ const Settings = () => {
 
   const close = () => setShowSettings(false);
 
+  const [qrCode, setQrCode] = createSignal<LoginQrCode | null>(null);
+  const [loginStatus, setLoginStatus] = createSignal<LoginStatus | null>(null);
+  let pollTimer: ReturnType<typeof setInterval> | undefined;
+
+  const stopPolling = () => {
+    clearInterval(pollTimer);
+    pollTimer = undefined;
+  };
+  onCleanup(stopPolling);
+
+  // 扫码成功后后端已写入配置文件，这里只同步 cookie，保留未保存的其他修改
+  const onLoginSuccess = async () => {
+    stopPolling();
+    setQrCode(null);
+
+    const saved = await readConfigFile();
+    setLsarConfig(
+      (prev) =>
+        prev && {
+          ...prev,
+          platform: { ...prev.platform, bilibili: saved.platform.bilibili },
+        },
+    );
+    refetchConfig();
+    setToast({ type: "success", message: "B 站登录成功，已保存 cookie" });
+  };
+
+  // 后端已写入配置文件，同步所有平台的 cookie
+  const onImportCookies = async () => {
+    try {
+      const imported = await importBrowserCookies();
+      const saved = await readConfigFile();
+      setLsarConfig((prev) => prev && { ...prev, platform: saved.platform });
+      refetchConfig();
+
+      const summary = imported
+        .map(
+          (item) =>
+            `${platforms()[item.platform]?.label ?? item.platform}（${item.browser}）`,
+        )
+        .join("、");
+      setToast({ type: "success", message: `已导入 ${summary} 的 cookie` });
+    } catch (e) {
+      setToast({ type: "error", message: String(e) });
+    }
+  };
+
+  const onQrLogin = async () => {
+    stopPolling();
+    setLoginStatus(null);
+
+    try {
+      const code = await getBilibiliLoginQrCode();
+      setQrCode(code);
+
+      pollTimer = setInterval(async () => {
+        try {
+          const status = await pollBilibiliLogin(code.key);
+          setLoginStatus(status);
+          if (status.status === "success") {
+            await onLoginSuccess();
+          } else if (status.status === "expired") {
+            stopPolling();
+          }
+        } catch (e) {
+          stopPolling();
+          setToast({ type: "error", message: String(e) });
+        }
+      }, 2000);
+    } catch (e) {
+      setToast({ type: "error", message: String(e) });
+    }
+  };
+
+  const loginStatusText = () => {
+    switch (loginStatus()?.status) {
+      case "scanned":
+        return "已扫码，请在手机上确认登录";
+      case "expired":
+        return "二维码已失效，请重新获取";
+      default:
+        return "请使用哔哩哔哩手机客户端扫码登录";
+    }
+  };
+
   const onSelectFile = async () => {
     const file: string | null = await open({
       multiple: false,
@@ -64,6 +163,8 @@ const Settings = () => {
   };
 
   const onCancel = () => {
+    stopPolling();
+    setQrCode(null);
     if (!lsarConfig()?.player.path) {
       // TODO: 关闭程序
     } else {
@@ -76,6 +177,9 @@ const Settings = () => {
     const p = lsarConfig()?.player.path;
     if (!p) return;
 
+    stopPolling();
+    setQrCode(null);
+
     const c = lsarConfig()!; // 到这里时 config 不可能为 undefined
     c.player.path = p;
 
@@ -116,7 +220,7 @@ const Settings = () => {
           <LazyLabel style={{ flex: 1 }}>B 站 Cookie</LazyLabel>
           <LazyTextArea
             style={{ flex: 3 }}
-            placeholder="不看 B 站直播无需配置此项"
+            placeholder="未设置时以游客身份解析 B 站直播，部分清晰度不可用"
             rows={6}
             value={lsarConfig()?.platform.bilibili.cookie}
             onInput={(s) =>
@@ -131,6 +235,24 @@ const Settings = () => {
           />
         </LazyFlex>
 
+        <LazySpace justify="end">
+          <LazyButton size="small" onClick={onImportCookies}>
+            从浏览器导入 cookie
+          </LazyButton>
+          <LazyButton size="small" onClick={onQrLogin}>
+            <Show when={qrCode()} fallback={"扫码登录 B 站"}>
+              刷新二维码
+            </Show>
+          </LazyButton>
+        </LazySpace>
+
+        <Show when={qrCode()}>
+          <LazyFlex direction="vertical" align="center" gap={4}>
+            <img src={qrCode()!.png} width={160} height={160} alt="B 站登录二维码" />
+            <LazyText type="secondary">{loginStatusText()}</LazyText>
+          </LazyFlex>
+        </Show>
+
         <LazySpace justify="around">
           <LazyButton
             danger
@@ -1,36 +1,35 @@
-import { error } from "~/command";
-import bilibili from "./bilibili";
-import douyin from "./douyin";
-import douyu from "./douyu";
-import huya from "./huya";
-import type LiveStreamParser from "./base";
-import { getSecondLevelDomain, WRONG_SECOND_LEVEL_DOMAIN } from "./utils";
+import { listen } from "@tauri-apps/api/event";
+import { createResource, createRoot } from "solid-js";
+import { error, evalResult, listPlatforms, parseURL } from "~/command";
 
 export const NOT_LIVE = Error("当前直播间未开播");
 export const IS_REPLAY = Error("当前直播间正在重播，本程序不解析重播源");
 
-export const platforms = {
-  douyu: {
-    label: "斗鱼",
-    roomBaseURL: "https://www.douyu.com/",
-    parser: douyu,
-  },
-  huya: {
-    label: "虎牙",
-    roomBaseURL: "https://www.huya.com/",
-    parser: huya,
-  },
-  bilibili: {
-    label: "B 站",
-    roomBaseURL: "https://live.bilibili.com/",
-    parser: bilibili,
-  },
-  douyin: {
-    label: "抖音",
-    roomBaseURL: "https://live.douyin.com/",
-    parser: douyin,
-  },
-} as const;
+// 后端内置 js 引擎执行失败时会请求在 webview 中执行，
+// 每个请求带有 id，多个解析同时进行时互不干扰
+listen<EvalRequest>("JS-EVAL", async ({ payload: { id, script } }) => {
+  let response: EvalResponse;
+  try {
+    response = { type: "ok", value: String(eval(script)) };
+  } catch (e) {
+    const err = e instanceof Error ? e : new Error(String(e));
+    response = {
+      type: "error",
+      name: err.name,
+      message: err.message,
+      stack: err.stack ?? null,
+    };
+  }
+  await evalResult(id, response);
+});
+
+// 平台列表来自后端的解析器注册表
+export const [platformList] = createRoot(() => createResource(listPlatforms));
+
+export const platforms = () =>
+  Object.fromEntries(
+    (platformList() ?? []).map((item) => [item.key, item]),
+  ) as Partial<Record<Platform, PlatformInfo>>;
 
 export const handleParsingError = (platform: Platform, e: unknown): Error => {
   const errorMessage = String(e);
@@ -47,51 +46,31 @@ export const handleParsingError = (platform: Platform, e: unknown): Error => {
       );
     default:
       error(platform, errorMessage);
-      return e as Error;
+      // 后端返回的错误是字符串
+      return e instanceof Error ? e : new Error(errorMessage);
   }
 };
 
 export const parse = async (
   platform: Platform,
   input: string | number,
-  config: Config,
-  setShowSettings: Setter<boolean>,
   setToast: AppContext[1]["setToast"],
   setParsedResult: AppContext[3]["setParsedResult"],
+  options?: ParseOptions,
 ) => {
   // 解析前先清空原有的解析结果
   setParsedResult(null);
 
-  if (
-    typeof input === "string" &&
-    getSecondLevelDomain(platforms[platform].roomBaseURL) !==
-      getSecondLevelDomain(input)
-  ) {
-    setToast({ type: "error", message: WRONG_SECOND_LEVEL_DOMAIN.message });
-    return;
-  }
+  // 房间号和主播 ID 使用 `平台:房间号` 的简写形式，链接则由后端识别平台，
+  // 短链接和分享链接的域名与直播间不同，不在此检查
+  const value = String(input);
+  const isRoomKey = /^[\w-]+$/.test(value);
 
-  let parser: LiveStreamParser | Error;
-
-  if (platform === "bilibili") {
-    if (!config.platform.bilibili.cookie.length) {
-      setShowSettings(true);
-      return;
-    }
-
-    parser = platforms.bilibili.parser(input, config.platform.bilibili.cookie);
-  } else {
-    parser = platforms[platform!].parser(input);
-  }
-
-  if (parser instanceof Error) {
-    setToast({ type: "error", message: parser.message });
-    return;
-  }
+  const text = isRoomKey ? `${platform}:${value}` : value;
 
   let result: ParsedResult | Error | null;
   try {
-    result = await parser.parse();
+    result = await parseURL(text, options);
   } catch (e) {
     result = handleParsingError(platform, e);
   }
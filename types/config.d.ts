@@ -5,5 +5,17 @@ interface Player {
 
 interface Config {
   player: Player;
-  platform: { bilibili: { cookie: string } };
+  platform: {
+    bilibili: { cookie: string };
+    douyin: { cookie: string };
+    huya: { cookie: string };
+  };
+  eval: { timeout: number };
+  monitor: {
+    enabled: boolean;
+    interval: number;
+    jitter: number;
+    request_interval: number;
+    platform_request_intervals: Partial<Record<Platform, number>>;
+  };
 }
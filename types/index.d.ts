@@ -1,17 +1,144 @@
-type Platform = "douyu" | "huya" | "bilibili" | "douyin";
+type Platform = "douyu" | "huya" | "bilibili" | "douyin" | "kuaishou" | "cc" | "twitch" | "youtube" | "acfun" | "generic";
+
+interface PlatformInfo {
+  key: Platform;
+  label: string;
+  roomBaseURL: string;
+  capabilities: { cookie: boolean; eval: boolean; roomName: boolean; liveStatus: boolean };
+}
+
+interface StreamLink {
+  url: string;
+  protocol: "flv" | "hls" | "fmp4" | "dash";
+  quality: string;
+  level: number;
+  codec: "avc" | "hevc" | "av1" | null;
+  bitrate: number | null;
+  cdn: string | null;
+  master: boolean;
+  expiresAt: number | null;
+  headers: Record<string, string>;
+}
 
 interface ParsedResult {
   platform: Platform;
   title: string;
   anchor: string;
-  roomID: number;
+  roomID: string;
   category: string;
-  links: string[];
+  links: StreamLink[];
+  qualities: QualityOption[];
+  currentQuality: number | null;
+  cdns: CDNOption[];
+  currentCDN: string | null;
+  notices: string[];
+}
+
+interface CDNOption {
+  value: string;
+  label: string;
+}
+
+interface LoginQrCode {
+  key: string;
+  url: string;
+  svg: string;
+  png: string;
+}
+
+type LoginStatus =
+  | { status: "waiting" }
+  | { status: "scanned" }
+  | { status: "expired" }
+  | { status: "success" };
+
+interface ImportedCookie {
+  platform: Platform;
+  browser: string;
+  count: number;
+}
+
+interface QualityOption {
+  value: number;
+  label: string;
+  loginRequired: boolean;
+}
+
+interface EvalRequest {
+  id: number;
+  script: string;
+}
+
+type EvalResponse =
+  | { type: "ok"; value: string }
+  | { type: "error"; name: string; message: string; stack: string | null };
+
+interface ParseOptions {
+  quality?: number;
+  protocol?: StreamLink["protocol"];
+  codec?: NonNullable<StreamLink["codec"]>;
+  cdn?: string;
+}
+
+interface FavoriteItem {
+  id: number;
+  platform: Platform;
+  room_id: string;
+  anchor: string;
+  alias: string;
+  tags: string[];
+  sort_order: number;
+  notify_live: boolean;
+  notify_offline: boolean;
+  notify_title: boolean;
+  created_at: string;
+}
+
+interface RoomQuery {
+  platform: Platform;
+  roomID: string;
+}
+
+interface RoomLiveStatus extends RoomQuery {
+  live: boolean;
+  title: string;
+  viewers: number | null;
+  /** 开播时间的 Unix 时间戳，单位秒 */
+  startTime: number | null;
+  error: string | null;
+}
+
+type LiveStatusChange =
+  | { kind: "wentLive" }
+  | { kind: "wentOffline" }
+  | { kind: "titleChanged"; previousTitle: string };
+
+type LiveStatusEvent = {
+  favoriteId: number;
+  platform: Platform;
+  roomID: string;
+  name: string;
+  title: string;
+} & LiveStatusChange;
+
+interface NewFavorite {
+  platform: Platform;
+  room_id: string;
+  anchor: string;
+  alias?: string;
+  tags?: string[];
+}
+
+interface FavoriteChanges {
+  alias?: string;
+  notify_live?: boolean;
+  notify_offline?: boolean;
+  notify_title?: boolean;
 }
 
 interface HistoryItem extends Omit<ParsedResult, "links" | "title" | "roomID"> {
   id: number;
   last_play_time: Date;
   last_title: string;
-  room_id: number;
+  room_id: string;
 }